@@ -0,0 +1,297 @@
+//! Puzzle inputs, read from a local cache and, with the `fetch` feature,
+//! downloaded into it when they are missing. Inputs are personal, so they are
+//! never checked in.
+
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "fetch")]
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+
+#[cfg(feature = "fetch")]
+const USER_AGENT: &str = "github.com/dannil/advent-of-code-2023";
+
+#[derive(Debug)]
+pub enum InputError {
+    /// Not in the cache, and no session token to download it with.
+    Missing {
+        day: u32,
+        path: PathBuf,
+    },
+    Download {
+        day: u32,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { day, path } => {
+                write!(
+                    f,
+                    "input for day {} is missing: save it to {}",
+                    day,
+                    path.display()
+                )?;
+                if cfg!(feature = "fetch") {
+                    write!(f, " or set AOC_SESSION to download it")?;
+                }
+                Ok(())
+            }
+            Self::Download { day, reason } => {
+                write!(f, "could not download input for day {}: {}", day, reason)
+            }
+            Self::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// The user's cache directory: `$XDG_CACHE_HOME` or `~/.cache`, and on
+/// Windows `%LOCALAPPDATA%`. Falls back to the temporary directory rather
+/// than one relative to wherever the program happens to run.
+fn cache_home(var: impl Fn(&str) -> Option<OsString>) -> PathBuf {
+    var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".cache")))
+        .or_else(|| var("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| var("USERPROFILE").map(|home| Path::new(&home).join("AppData").join("Local")))
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(env::temp_dir)
+}
+
+pub struct Inputs {
+    cache_dir: PathBuf,
+    #[cfg(feature = "fetch")]
+    base_url: String,
+    #[cfg(feature = "fetch")]
+    session: Option<String>,
+}
+
+impl Inputs {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Inputs {
+            cache_dir: cache_dir.into(),
+            #[cfg(feature = "fetch")]
+            base_url: DEFAULT_BASE_URL.to_string(),
+            #[cfg(feature = "fetch")]
+            session: None,
+        }
+    }
+
+    /// Configured from `AOC_CACHE_DIR`, and with the `fetch` feature from
+    /// `AOC_BASE_URL` and `AOC_SESSION`. The cache defaults to
+    /// `advent-of-code-2023` in the user's cache directory.
+    pub fn from_env() -> Self {
+        let cache_dir = match env::var_os("AOC_CACHE_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => cache_home(|name| env::var_os(name)).join("advent-of-code-2023"),
+        };
+        let inputs = Inputs::new(cache_dir);
+        #[cfg(feature = "fetch")]
+        let inputs = inputs
+            .base_url(env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()))
+            .session(env::var("AOC_SESSION").unwrap_or_default());
+        inputs
+    }
+
+    #[cfg(feature = "fetch")]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The value of the `session` cookie of a logged in browser.
+    #[cfg(feature = "fetch")]
+    pub fn session(mut self, session: impl Into<String>) -> Self {
+        let session = session.into();
+        self.session = Some(session.trim().to_string()).filter(|s| !s.is_empty());
+        self
+    }
+
+    /// Where the input for `day` is cached.
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{}.txt", day))
+    }
+
+    /// The input for `day`, downloading it into the cache if it is not there.
+    pub fn get(&self, day: u32) -> Result<String, InputError> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(input),
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(InputError::Io { path, error })
+            }
+            #[cfg(feature = "fetch")]
+            Err(_) => self.fetch(day, path),
+            #[cfg(not(feature = "fetch"))]
+            Err(_) => Err(InputError::Missing { day, path }),
+        }
+    }
+
+    #[cfg(feature = "fetch")]
+    fn fetch(&self, day: u32, path: PathBuf) -> Result<String, InputError> {
+        let session = match &self.session {
+            Some(session) => session,
+            None => return Err(InputError::Missing { day, path }),
+        };
+        let input = self.download(day, session)?;
+        let io_error = |error| InputError::Io {
+            path: path.clone(),
+            error,
+        };
+        fs::create_dir_all(&self.cache_dir).map_err(io_error)?;
+        fs::write(&path, &input).map_err(io_error)?;
+        Ok(input)
+    }
+
+    #[cfg(feature = "fetch")]
+    fn download(&self, day: u32, session: &str) -> Result<String, InputError> {
+        let url = format!("{}/day/{}/input", self.base_url.trim_end_matches('/'), day);
+        let download_error = |reason: String| InputError::Download { day, reason };
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|error| match error {
+                ureq::Error::Status(status, _) => {
+                    download_error(format!("{} answered with status {}", url, status))
+                }
+                ureq::Error::Transport(transport) => download_error(transport.to_string()),
+            })?;
+        response
+            .into_string()
+            .map_err(|error| download_error(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "fetch")]
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+
+    fn cache_dir() -> PathBuf {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = env::temp_dir().join(format!(
+            "aoc-inputs-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Answers one request with `status` and `body`, returning the request
+    /// line and headers it was sent.
+    #[cfg(feature = "fetch")]
+    fn stand_in(status: &'static str, body: &'static str) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(|l| l.unwrap())
+                .take_while(|l| !l.is_empty())
+                .collect();
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (base_url, handle)
+    }
+
+    #[test]
+    pub fn cache_home_of_the_platform() {
+        let home = |vars: &'static [(&'static str, &'static str)]| {
+            cache_home(move |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| OsString::from(value))
+            })
+        };
+        assert_eq!(
+            home(&[("XDG_CACHE_HOME", "/xdg"), ("HOME", "/home/elf")]),
+            Path::new("/xdg")
+        );
+        assert_eq!(
+            home(&[("HOME", "/home/elf")]),
+            Path::new("/home/elf/.cache")
+        );
+        assert_eq!(home(&[("HOME", "relative")]), env::temp_dir());
+        assert_eq!(home(&[]), env::temp_dir());
+    }
+
+    #[test]
+    pub fn reads_cached_input() {
+        let dir = cache_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day6.txt"), "Time: 7\nDistance: 9\n").unwrap();
+        let inputs = Inputs::new(&dir);
+        assert_eq!(inputs.get(6).unwrap(), "Time: 7\nDistance: 9\n");
+    }
+
+    #[test]
+    pub fn missing_without_session() {
+        let dir = cache_dir();
+        let error = Inputs::new(&dir).get(4).unwrap_err();
+        assert!(matches!(error, InputError::Missing { day: 4, .. }));
+        assert!(error.to_string().starts_with("input for day 4 is missing"));
+    }
+
+    #[test]
+    #[cfg(feature = "fetch")]
+    pub fn downloads_and_caches_input() {
+        let dir = cache_dir();
+        let (base_url, server) = stand_in("200 OK", "seeds: 79 14\n");
+        let inputs = Inputs::new(&dir).base_url(base_url).session("abc123");
+        assert_eq!(inputs.get(5).unwrap(), "seeds: 79 14\n");
+
+        let request = server.join().unwrap();
+        assert_eq!(request[0], "GET /2023/day/5/input HTTP/1.1");
+        assert!(request.contains(&"Cookie: session=abc123".to_string()));
+        // The stand-in is gone, so this can only come from the cache
+        assert_eq!(inputs.get(5).unwrap(), "seeds: 79 14\n");
+        assert_eq!(
+            fs::read_to_string(inputs.path(5)).unwrap(),
+            "seeds: 79 14\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fetch")]
+    pub fn reports_failed_downloads() {
+        let dir = cache_dir();
+        let (base_url, server) = stand_in("400 Bad Request", "Please log in");
+        let inputs = Inputs::new(&dir).base_url(base_url).session("expired");
+        let error = inputs.get(2).unwrap_err();
+        server.join().unwrap();
+        assert!(matches!(error, InputError::Download { day: 2, .. }));
+        assert!(error.to_string().contains("status 400"));
+        assert!(!inputs.path(2).exists());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod inputs;
+pub mod numbers;
+pub mod parse;
+pub mod puzzle;
+pub mod snapshot;
+pub mod table;
+pub mod testing;
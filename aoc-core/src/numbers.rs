@@ -0,0 +1,247 @@
+use std::{error::Error, fmt, iter::FusedIterator, marker::PhantomData};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberErrorKind {
+    /// The token is not made up of digits (and, for signed numbers, a sign).
+    Invalid,
+    /// The token is a number, but does not fit in the type being read.
+    Overflow,
+}
+
+/// A token that could not be read as a number, given as the byte range it
+/// spans in the scanned input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberError {
+    pub start: usize,
+    pub end: usize,
+    pub kind: NumberErrorKind,
+}
+
+impl NumberError {
+    /// The offending token in `input`, which must be the scanned input.
+    pub fn token<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.start..self.end]
+    }
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            NumberErrorKind::Invalid => {
+                write!(f, "invalid number at bytes {}..{}", self.start, self.end)
+            }
+            NumberErrorKind::Overflow => {
+                write!(
+                    f,
+                    "number at bytes {}..{} is too large",
+                    self.start, self.end
+                )
+            }
+        }
+    }
+}
+
+impl Error for NumberError {}
+
+/// An integer type that can be read from a whitespace-separated token.
+pub trait Number: Sized {
+    fn from_token(token: &[u8]) -> Result<Self, NumberErrorKind>;
+}
+
+fn digits_value<T>(
+    digits: &[u8],
+    zero: T,
+    step: impl Fn(T, T) -> Option<T>,
+    digit: impl Fn(u8) -> T,
+) -> Result<T, NumberErrorKind> {
+    if digits.is_empty() || !digits.iter().all(|b| b.is_ascii_digit()) {
+        return Err(NumberErrorKind::Invalid);
+    }
+    digits.iter().try_fold(zero, |value, &b| {
+        step(value, digit(b - b'0')).ok_or(NumberErrorKind::Overflow)
+    })
+}
+
+macro_rules! unsigned_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_token(token: &[u8]) -> Result<Self, NumberErrorKind> {
+                digits_value(token, 0, |v, d| v.checked_mul(10)?.checked_add(d), <$t>::from)
+            }
+        }
+    )*};
+}
+
+macro_rules! signed_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_token(token: &[u8]) -> Result<Self, NumberErrorKind> {
+                // Negative numbers are built downwards so that MIN can be read
+                match token.split_first() {
+                    Some((b'-', digits)) => {
+                        digits_value(digits, 0, |v, d| v.checked_mul(10)?.checked_sub(d), <$t>::from)
+                    }
+                    Some((b'+', digits)) => {
+                        digits_value(digits, 0, |v, d| v.checked_mul(10)?.checked_add(d), <$t>::from)
+                    }
+                    _ => digits_value(token, 0, |v, d| v.checked_mul(10)?.checked_add(d), <$t>::from),
+                }
+            }
+        }
+    )*};
+}
+
+unsigned_number!(u8, u16, u32, u64, u128);
+signed_number!(i16, i32, i64, i128);
+
+/// Byte range of the next whitespace-separated token at or after `position`.
+fn next_token(input: &str, position: usize) -> Option<(usize, usize)> {
+    let bytes = input.as_bytes();
+    let start = position
+        + bytes[position..]
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())?;
+    let end = bytes[start..]
+        .iter()
+        .position(|b| b.is_ascii_whitespace())
+        .map_or(bytes.len(), |length| start + length);
+    Some((start, end))
+}
+
+/// Reads the whitespace-separated numbers in a string one at a time, without
+/// allocating. A token that is not a number is returned as an error, after
+/// which scanning carries on with the next token.
+#[derive(Debug, Clone)]
+pub struct Numbers<'a, T> {
+    input: &'a str,
+    position: usize,
+    number: PhantomData<T>,
+}
+
+impl<'a, T: Number> Numbers<'a, T> {
+    pub fn new(input: &'a str) -> Self {
+        Numbers {
+            input,
+            position: 0,
+            number: PhantomData,
+        }
+    }
+}
+
+impl<T: Number> Iterator for Numbers<'_, T> {
+    type Item = Result<T, NumberError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = next_token(self.input, self.position)?;
+        self.position = end;
+        Some(
+            T::from_token(&self.input.as_bytes()[start..end]).map_err(|kind| NumberError {
+                start,
+                end,
+                kind,
+            }),
+        )
+    }
+}
+
+impl<T: Number> FusedIterator for Numbers<'_, T> {}
+
+/// Unsigned numbers in `input`, such as `79 14 55 13`.
+pub fn unsigned(input: &str) -> Numbers<'_, u64> {
+    Numbers::new(input)
+}
+
+/// Signed numbers in `input`, such as `-3 +4 5`.
+pub fn signed(input: &str) -> Numbers<'_, i64> {
+    Numbers::new(input)
+}
+
+/// Reads the whitespace-separated runs of digits in a string as they were
+/// written, for numbers of any size.
+#[derive(Debug, Clone)]
+pub struct Digits<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Iterator for Digits<'a> {
+    type Item = Result<&'a str, NumberError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = next_token(self.input, self.position)?;
+        self.position = end;
+        let token = &self.input[start..end];
+        if token.bytes().all(|b| b.is_ascii_digit()) {
+            Some(Ok(token))
+        } else {
+            Some(Err(NumberError {
+                start,
+                end,
+                kind: NumberErrorKind::Invalid,
+            }))
+        }
+    }
+}
+
+impl FusedIterator for Digits<'_> {}
+
+/// Runs of digits in `input`, such as `7  15   30`.
+pub fn digits(input: &str) -> Digits<'_> {
+    Digits { input, position: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn unsigned_numbers() {
+        let numbers: Result<Vec<u64>, _> = unsigned("  79 14\t55\n13 ").collect();
+        assert_eq!(numbers, Ok(vec![79, 14, 55, 13]));
+        assert_eq!(unsigned("").next(), None);
+        assert_eq!(unsigned(" \n ").next(), None);
+    }
+
+    #[test]
+    pub fn signed_numbers() {
+        let numbers: Result<Vec<i64>, _> = signed("-3 +4 5 -9223372036854775808").collect();
+        assert_eq!(numbers, Ok(vec![-3, 4, 5, i64::MIN]));
+    }
+
+    #[test]
+    pub fn errors_point_at_the_token() {
+        let input = "12 x4 -3 99999999999999999999 7";
+        let numbers: Vec<Result<u64, NumberError>> = unsigned(input).collect();
+        assert_eq!(numbers[0], Ok(12));
+        let error = numbers[1].unwrap_err();
+        assert_eq!(error.kind, NumberErrorKind::Invalid);
+        assert_eq!(error.token(input), "x4");
+        assert_eq!(numbers[2].unwrap_err().token(input), "-3");
+        let error = numbers[3].unwrap_err();
+        assert_eq!(error.kind, NumberErrorKind::Overflow);
+        assert_eq!((error.start, error.end), (9, 29));
+        assert_eq!(error.to_string(), "number at bytes 9..29 is too large");
+        assert_eq!(numbers[4], Ok(7));
+    }
+
+    #[test]
+    pub fn narrower_types() {
+        assert_eq!(Numbers::<u8>::new("255").next(), Some(Ok(255)));
+        assert_eq!(
+            Numbers::<u8>::new("256").next().unwrap().unwrap_err().kind,
+            NumberErrorKind::Overflow
+        );
+        assert_eq!(
+            Numbers::<i16>::new("-").next().unwrap().unwrap_err().kind,
+            NumberErrorKind::Invalid
+        );
+    }
+
+    #[test]
+    pub fn digits_of_any_size() {
+        let input = "7  15 123456789012345678901234567890 -1";
+        let digits: Vec<Result<&str, NumberError>> = digits(input).collect();
+        assert_eq!(digits[2], Ok("123456789012345678901234567890"));
+        assert_eq!(digits[3].unwrap_err().token(input), "-1");
+    }
+}
@@ -0,0 +1,522 @@
+//! Small parser combinators for the puzzle input formats. A parser is any
+//! function taking the input left to parse and returning what it parsed along
+//! with the input after it, or a [`Failure`] saying what it expected instead.
+
+use std::{any::type_name, error::Error, fmt};
+
+use super::numbers::{self, Digits, Number, NumberErrorKind, Numbers};
+
+/// Where a parser gave up, as the input left at that point, and what it
+/// expected to find there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<'a> {
+    pub remaining: &'a str,
+    pub expected: String,
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+fn fail<T>(remaining: &str, expected: impl Into<String>) -> PResult<'_, T> {
+    Err(Failure {
+        remaining,
+        expected: expected.into(),
+    })
+}
+
+fn split_while(input: &str, pred: impl Fn(char) -> bool) -> (&str, &str) {
+    input.split_at(input.find(|c| !pred(c)).unwrap_or(input.len()))
+}
+
+/// Exactly `literal`.
+pub fn literal<'a>(literal: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input| match input.strip_prefix(literal) {
+        Some(rest) => Ok((&input[..literal.len()], rest)),
+        None => fail(input, format!("\"{}\"", literal)),
+    }
+}
+
+/// Spaces and tabs, if there are any.
+pub fn spaces<'a>() -> impl Fn(&'a str) -> PResult<'a, ()> {
+    |input| Ok(((), input.trim_start_matches([' ', '\t'])))
+}
+
+/// `parser`, after any spaces or tabs.
+pub fn spaced<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    preceded(spaces(), parser)
+}
+
+/// A run of ASCII digits, as written.
+pub fn digits<'a>() -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    |input| match split_while(input, |c| c.is_ascii_digit()) {
+        ("", _) => fail(input, "number"),
+        (digits, rest) => Ok((digits, rest)),
+    }
+}
+
+fn expected_number<T>(kind: NumberErrorKind) -> String {
+    match kind {
+        NumberErrorKind::Invalid => "number".to_string(),
+        NumberErrorKind::Overflow => format!("number that fits in {}", type_name::<T>()),
+    }
+}
+
+fn number<'a, T: Number>(input: &'a str, token: &'a str, rest: &'a str) -> PResult<'a, T> {
+    match T::from_token(token.as_bytes()) {
+        Ok(number) => Ok((number, rest)),
+        Err(kind) => fail(input, expected_number::<T>(kind)),
+    }
+}
+
+/// A number made of ASCII digits.
+pub fn unsigned<'a, T: Number>() -> impl Fn(&'a str) -> PResult<'a, T> {
+    |input| {
+        let (token, rest) = digits()(input)?;
+        number(input, token, rest)
+    }
+}
+
+/// A number made of ASCII digits, optionally preceded by `-` or `+`.
+pub fn signed<'a, T: Number>() -> impl Fn(&'a str) -> PResult<'a, T> {
+    |input| {
+        let (_, after_sign) = split_while(input, |c| c == '-' || c == '+');
+        let sign_length = (input.len() - after_sign.len()).min(1);
+        let (digits, rest) = digits()(&input[sign_length..])?;
+        number(input, &input[..sign_length + digits.len()], rest)
+    }
+}
+
+/// The space-separated tokens at the start of `input` that look like numbers,
+/// and the input after them.
+fn number_run(input: &str) -> (&str, &str) {
+    split_while(input, |c| {
+        c.is_ascii_digit() || matches!(c, ' ' | '\t' | '-' | '+')
+    })
+}
+
+/// Space-separated numbers, possibly none, handed back as a [`Numbers`]
+/// scanner instead of being collected. Every number is checked first, so the
+/// scanner only yields `Ok`s, and a token that does not fit `T` fails here.
+pub fn numbers<'a, T: Number>() -> impl Fn(&'a str) -> PResult<'a, Numbers<'a, T>> {
+    |input| {
+        let (run, rest) = number_run(input);
+        match Numbers::<T>::new(run).find_map(Result::err) {
+            Some(error) => fail(&input[error.start..], expected_number::<T>(error.kind)),
+            None => Ok((Numbers::new(run), rest)),
+        }
+    }
+}
+
+/// Space-separated runs of digits, possibly none, handed back as a [`Digits`]
+/// scanner after checking them like [`numbers`].
+pub fn digit_groups<'a>() -> impl Fn(&'a str) -> PResult<'a, Digits<'a>> {
+    |input| {
+        let (run, rest) = number_run(input);
+        match numbers::digits(run).find_map(Result::err) {
+            Some(error) => fail(&input[error.start..], expected_number::<u8>(error.kind)),
+            None => Ok((numbers::digits(run), rest)),
+        }
+    }
+}
+
+/// A run of ASCII letters and digits, such as a color or category name.
+pub fn word<'a>() -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    |input| match split_while(input, |c| c.is_ascii_alphanumeric()) {
+        ("", _) => fail(input, "word"),
+        (word, rest) => Ok((word, rest)),
+    }
+}
+
+/// Everything that is left.
+pub fn rest<'a>() -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    |input| Ok((input, &input[input.len()..]))
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    f: impl Fn(T) -> U,
+) -> impl Fn(&'a str) -> PResult<'a, U> {
+    move |input| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+/// `parser`, followed by a check of what it parsed. A check that fails is
+/// reported at the start of the parsed value, expecting the given message.
+pub fn verify<'a, T, U>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    f: impl Fn(T) -> Result<U, String>,
+) -> impl Fn(&'a str) -> PResult<'a, U> {
+    move |input| {
+        let (value, rest) = parser(input)?;
+        match f(value) {
+            Ok(value) => Ok((value, rest)),
+            Err(expected) => fail(input.trim_start_matches([' ', '\t']), expected),
+        }
+    }
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Fn(&'a str) -> PResult<'a, A>,
+    second: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, (A, B)> {
+    move |input| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+/// `second`, after `first`.
+pub fn preceded<'a, A, B>(
+    first: impl Fn(&'a str) -> PResult<'a, A>,
+    second: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, B> {
+    map(pair(first, second), |(_, b)| b)
+}
+
+/// `first`, followed by `second`.
+pub fn terminated<'a, A, B>(
+    first: impl Fn(&'a str) -> PResult<'a, A>,
+    second: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, A> {
+    map(pair(first, second), |(a, _)| a)
+}
+
+/// `first`, or `second` if that fails. When both fail, the one that got
+/// further is reported.
+pub fn alt<'a, T>(
+    first: impl Fn(&'a str) -> PResult<'a, T>,
+    second: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input| {
+        let first_failure = match first(input) {
+            Ok(parsed) => return Ok(parsed),
+            Err(failure) => failure,
+        };
+        let second_failure = match second(input) {
+            Ok(parsed) => return Ok(parsed),
+            Err(failure) => failure,
+        };
+        use std::cmp::Ordering::*;
+        match first_failure
+            .remaining
+            .len()
+            .cmp(&second_failure.remaining.len())
+        {
+            Less => Err(first_failure),
+            Greater => Err(second_failure),
+            Equal => fail(
+                first_failure.remaining,
+                format!("{} or {}", first_failure.expected, second_failure.expected),
+            ),
+        }
+    }
+}
+
+/// `parser` as many times as it matches, possibly none.
+pub fn many<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |mut input| {
+        let mut values = Vec::new();
+        while let Ok((value, rest)) = parser(input) {
+            if rest.len() == input.len() {
+                break;
+            }
+            values.push(value);
+            input = rest;
+        }
+        Ok((values, input))
+    }
+}
+
+/// Space-separated `item`s, possibly none.
+pub fn list<'a, T>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    many(spaced(item))
+}
+
+/// One or more `item`s with `separator` between them.
+pub fn separated<'a, T, S>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+    separator: impl Fn(&'a str) -> PResult<'a, S>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input| {
+        let (first, mut input) = item(input)?;
+        let mut values = vec![first];
+        while let Ok((_, rest)) = separator(input) {
+            let (value, rest) = item(rest)?;
+            values.push(value);
+            input = rest;
+        }
+        Ok((values, input))
+    }
+}
+
+/// `value` after a `label:` heading, such as `Time: 7 15 30`.
+pub fn labelled<'a, T>(
+    label: &'static str,
+    value: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    preceded(pair(spaced(literal(label)), spaced(literal(":"))), value)
+}
+
+/// Why a line could not be parsed, pointing at the first character that did
+/// not fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    /// The token found instead, or `None` at the end of the line.
+    pub found: Option<String>,
+}
+
+impl ParseError {
+    fn new(input: &str, failure: Failure) -> Self {
+        let remaining = failure.remaining.trim_start();
+        let offset = input.len() - remaining.len();
+        let found = remaining.split_whitespace().next();
+        ParseError {
+            line: 1,
+            column: input[..offset].chars().count() + 1,
+            expected: failure.expected,
+            found: found.map(|token| token.to_string()),
+        }
+    }
+
+    /// The same error, for a line that is `line` lines into the input.
+    pub fn on_line(self, line: usize) -> Self {
+        ParseError { line, ..self }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        match &self.found {
+            Some(found) => write!(f, "\"{}\"", found),
+            None => write!(f, "end of line"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses all of `line`, allowing for whitespace at its end.
+pub fn parse_line<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    line: &'a str,
+) -> Result<T, ParseError> {
+    let line = line.trim_end();
+    match parser(line) {
+        Ok((value, "")) => Ok(value),
+        Ok((_, rest)) => Err(ParseError::new(
+            line,
+            Failure {
+                remaining: rest,
+                expected: "end of line".to_string(),
+            },
+        )),
+        Err(failure) => Err(ParseError::new(line, failure)),
+    }
+}
+
+/// Parses every non-blank line of `input`, numbering lines from 1.
+pub fn parse_lines<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    input: &'a str,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_line(&parser, line).map_err(|e| e.on_line(index + 1)))
+        .collect()
+}
+
+/// A header line, as parsed, and the numbered body lines after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section<'a, H> {
+    pub header_line: usize,
+    pub header: H,
+    pub lines: Vec<(usize, &'a str)>,
+}
+
+/// Groups numbered `lines` into sections, starting a new one at every line
+/// that `header` matches the start of. Blank lines are skipped, and a body
+/// line before the first header fails with what `header` expected there.
+pub fn sections<'a, H>(
+    header: impl Fn(&'a str) -> PResult<'a, H>,
+    lines: impl IntoIterator<Item = (usize, &'a str)>,
+) -> Result<Vec<Section<'a, H>>, ParseError> {
+    let mut sections: Vec<Section<H>> = Vec::new();
+    for (line_number, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match (header(line), sections.last_mut()) {
+            (Ok((header, _)), _) => sections.push(Section {
+                header_line: line_number,
+                header,
+                lines: Vec::new(),
+            }),
+            (Err(_), Some(section)) => section.lines.push((line_number, line)),
+            (Err(failure), None) => return Err(ParseError::new(line, failure).on_line(line_number)),
+        }
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn labelled_list() {
+        let row = labelled("Time", list(unsigned::<u64>()));
+        assert_eq!(
+            parse_line(&row, "Time:      7  15   30"),
+            Ok(vec![7, 15, 30])
+        );
+        assert_eq!(parse_line(&row, "  Time :7 "), Ok(vec![7]));
+        assert_eq!(parse_line(&row, "Time:"), Ok(vec![]));
+    }
+
+    #[test]
+    pub fn numbers_without_collecting() {
+        let row = labelled("seeds", numbers::<u64>());
+        let (seeds, rest) = row("seeds: 79 14\t55 13 | x").unwrap();
+        assert_eq!(seeds.flatten().collect::<Vec<u64>>(), vec![79, 14, 55, 13]);
+        assert_eq!(rest, "| x");
+        assert_eq!(parse_line(&row, "seeds:").unwrap().count(), 0);
+
+        let error = parse_line(&row, "seeds: 79 -14 55").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 11: expected number, found \"-14\""
+        );
+        let error = parse_line(numbers::<u8>(), "1 256").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (3, "number that fits in u8")
+        );
+
+        let (digits, _) = digit_groups()("7  15 123456789012345678901234567890").unwrap();
+        let digits: Vec<&str> = digits.flatten().collect();
+        assert_eq!(digits, vec!["7", "15", "123456789012345678901234567890"]);
+    }
+
+    #[test]
+    pub fn separated_items() {
+        let cubes = pair(spaced(unsigned::<u32>()), spaced(word()));
+        let rounds = separated(separated(cubes, spaced(literal(","))), spaced(literal(";")));
+        assert_eq!(
+            parse_line(&rounds, "3 blue, 4 red; 2 green"),
+            Ok(vec![vec![(3, "blue"), (4, "red")], vec![(2, "green")]])
+        );
+    }
+
+    #[test]
+    pub fn signed_numbers() {
+        let numbers = list(signed::<i32>());
+        assert_eq!(parse_line(&numbers, "-3 +4 5"), Ok(vec![-3, 4, 5]));
+        assert_eq!(
+            parse_line(&numbers, "1 -").unwrap_err().expected,
+            "end of line"
+        );
+    }
+
+    #[test]
+    pub fn errors_point_at_the_problem() {
+        let card = pair(
+            preceded(spaced(literal("Card")), spaced(unsigned::<u32>())),
+            preceded(spaced(literal(":")), list(unsigned::<u32>())),
+        );
+        let error = parse_line(&card, "Card 1: 41 x8").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 12: expected end of line, found \"x8\""
+        );
+        let error = parse_line(&card, "Card: 41").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 5: expected number, found \":\""
+        );
+        let error = parse_line(&card, "Card 4294967296: 41").unwrap_err();
+        assert_eq!(error.expected, "number that fits in u32");
+        assert_eq!(error.column, 6);
+    }
+
+    #[test]
+    pub fn alt_reports_the_furthest_failure() {
+        let row = alt(
+            map(labelled("Time", list(digits())), |d| ("Time", d)),
+            map(labelled("Distance", list(digits())), |d| ("Distance", d)),
+        );
+        assert_eq!(
+            parse_line(&row, "Distance: 9 40"),
+            Ok(("Distance", vec!["9", "40"]))
+        );
+        let error = parse_line(&row, "Speed: 9").unwrap_err();
+        assert_eq!(error.expected, "\"Time\" or \"Distance\"");
+        assert_eq!(error.column, 1);
+    }
+
+    #[test]
+    pub fn verify_reports_at_the_value() {
+        let even = verify(spaced(unsigned::<u32>()), |n| {
+            if n % 2 == 0 {
+                Ok(n)
+            } else {
+                Err("even number".to_string())
+            }
+        });
+        assert_eq!(parse_line(list(&even), "2 4"), Ok(vec![2, 4]));
+        let error = parse_line(pair(&even, &even), "2  5").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (4, "even number"));
+    }
+
+    #[test]
+    pub fn sections_group_body_lines() {
+        let header = terminated(word(), literal(":"));
+        let input = "a:\n1 2\n\nb: 3\n4";
+        let found = sections(&header, (1..).zip(input.lines())).unwrap();
+        assert_eq!(
+            found,
+            vec![
+                Section {
+                    header_line: 1,
+                    header: "a",
+                    lines: vec![(2, "1 2")]
+                },
+                Section {
+                    header_line: 4,
+                    header: "b",
+                    lines: vec![(5, "4")]
+                },
+            ]
+        );
+        let error = sections(&header, (1..).zip("\n1 2\na:".lines())).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected \":\", found \"2\""
+        );
+    }
+
+    #[test]
+    pub fn parse_lines_numbers_lines() {
+        let input = "1 2\n\n3 x\n";
+        let error = parse_lines(list(unsigned::<u8>()), input).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(
+            parse_lines(list(unsigned::<u8>()), "1 2\n\n3"),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+    }
+}
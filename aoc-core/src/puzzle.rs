@@ -0,0 +1,24 @@
+/// An example from a puzzle description and the answer it should give.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Example {
+    pub part: u32,
+    pub input: &'static str,
+    pub answer: u64,
+}
+
+/// How a solution takes its input.
+pub trait FromInput {
+    fn from_input(input: &str) -> Self;
+}
+
+impl FromInput for String {
+    fn from_input(input: &str) -> Self {
+        input.lines().collect::<Vec<&str>>().join("\n")
+    }
+}
+
+impl FromInput for Vec<String> {
+    fn from_input(input: &str) -> Self {
+        input.lines().map(|l| l.to_string()).collect()
+    }
+}
@@ -0,0 +1,173 @@
+//! Golden-file tests for solver output that is richer than a single answer,
+//! such as reports and traces.
+//!
+//! Snapshots are checked in and compared on every run. A missing snapshot
+//! fails like a changed one, so set `AOC_UPDATE_SNAPSHOTS=1` to record new
+//! snapshots or record changed ones again.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path,
+};
+
+/// The environment variable that switches snapshot tests to update mode.
+pub const UPDATE_VAR: &str = "AOC_UPDATE_SNAPSHOTS";
+
+fn updating() -> bool {
+    env::var(UPDATE_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Compares `actual` with `<snapshots_dir>/<name>.snap`, or records it there
+/// in update mode.
+pub fn check(snapshots_dir: &str, name: &str, actual: &str) {
+    compare(snapshots_dir, name, actual, updating());
+}
+
+fn compare(snapshots_dir: &str, name: &str, actual: &str, update: bool) {
+    let path = Path::new(snapshots_dir).join(format!("{}.snap", name));
+    let actual = normalize(actual);
+    if update {
+        fs::create_dir_all(snapshots_dir)
+            .and_then(|_| fs::write(&path, &actual))
+            .unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+        let _ = writeln!(io::stderr(), "recorded: {}", path.display());
+        return;
+    }
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => normalize(&expected),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => panic!(
+            "{} is missing (set {}=1 to record it), the output was:\n{}",
+            path.display(),
+            UPDATE_VAR,
+            actual
+        ),
+        Err(error) => panic!("{}: {}", path.display(), error),
+    };
+    if actual != expected {
+        panic!(
+            "{} does not match (set {}=1 to update it):\n{}",
+            path.display(),
+            UPDATE_VAR,
+            diff(&expected, &actual)
+        );
+    }
+}
+
+fn normalize(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+        .iter()
+        .map(|l| format!("{}\n", l.trim_end()))
+        .collect()
+}
+
+/// A line diff from `expected` to `actual`, marking removed lines with `-`,
+/// added lines with `+` and unchanged lines with a space.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..].
+    let mut common = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Checks a rendered output against its snapshot in the crate's
+/// `tests/snapshots` directory, such as
+///
+/// ```ignore
+/// aoc_core::assert_snapshot!("trace_example", render_table(&traces));
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::snapshot::check(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"),
+            $name,
+            &$actual,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshots_dir(name: &str) -> String {
+        let dir = env::temp_dir().join(format!("aoc-snapshot-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "report.snap is missing (set AOC_UPDATE_SNAPSHOTS=1 to record it), \
+                               the output was:\na\nb\n"
+    )]
+    pub fn fails_missing_snapshots() {
+        let dir = snapshots_dir("missing");
+        compare(&dir, "report", "a\nb", false);
+    }
+
+    #[test]
+    pub fn records_snapshots_in_update_mode() {
+        let dir = snapshots_dir("record");
+        compare(&dir, "report", "a\nb  \n\n", true);
+        let recorded = fs::read_to_string(Path::new(&dir).join("report.snap")).unwrap();
+        assert_eq!(recorded, "a\nb\n");
+        compare(&dir, "report", "a\r\nb", false);
+    }
+
+    #[test]
+    #[should_panic(expected = "  a\n- b\n+ c")]
+    pub fn fails_changed_output() {
+        let dir = snapshots_dir("fail");
+        compare(&dir, "report", "a\nb", true);
+        compare(&dir, "report", "a\nc", false);
+    }
+
+    #[test]
+    pub fn updates_snapshots() {
+        let dir = snapshots_dir("update");
+        compare(&dir, "report", "a\nb", true);
+        compare(&dir, "report", "a\nc", true);
+        compare(&dir, "report", "a\nc", false);
+    }
+
+    #[test]
+    pub fn diffs_lines() {
+        assert_eq!(diff("a\nb\nc\nd", "a\nc\nd\ne"), "  a\n- b\n  c\n  d\n+ e");
+        assert_eq!(diff("", "a"), "+ a");
+        assert_eq!(diff("a", ""), "- a");
+    }
+}
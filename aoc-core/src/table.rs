@@ -0,0 +1,59 @@
+//! Plain text tables for the diagnostics the days print.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Renders `rows` under `header`, with every column as wide as its widest
+/// cell and columns separated by `|`.
+pub fn render<const N: usize>(header: [(&str, Align); N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(|(name, _)| name.len());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    let mut write_row = |cells: [&str; N]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .zip(&header)
+            .map(|((cell, &width), (_, align))| match align {
+                Align::Left => format!("{:<width$}", cell),
+                Align::Right => format!("{:>width$}", cell),
+            })
+            .collect();
+        writeln!(table, "{}", line.join(" | ").trim_end()).unwrap();
+    };
+    write_row(header.map(|(name, _)| name));
+    for row in rows {
+        write_row(row.each_ref().map(|cell| cell.as_str()));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn pads_columns_to_the_widest_cell() {
+        let rows = [
+            ["1".to_string(), "a, b".to_string()],
+            ["100".to_string(), "-".to_string()],
+        ];
+        let table = render([("Id", Align::Right), ("Names", Align::Left)], &rows);
+        assert_eq!(table, " Id | Names\n  1 | a, b\n100 | -\n");
+    }
+
+    #[test]
+    pub fn header_only() {
+        assert_eq!(render([("Id", Align::Right)], &[]), "Id\n");
+    }
+}
@@ -0,0 +1,211 @@
+//! Support for tests that check puzzle answers against input files, which
+//! are personal and so are not checked in.
+
+use std::{
+    fmt::Debug,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use super::{inputs::Inputs, puzzle::Example};
+
+/// Reads `<inputs_dir>/<name>.txt`. The actual input is also looked for in the
+/// input cache, but never downloaded, so tests stay offline.
+pub fn load(inputs_dir: &str, day: u32, name: &str) -> Result<Option<String>, String> {
+    let mut paths = vec![Path::new(inputs_dir).join(format!("{}.txt", name))];
+    if name == "actual" {
+        paths.push(Inputs::from_env().path(day));
+    }
+    for path in &paths {
+        match fs::read_to_string(path) {
+            Ok(input) => return Ok(Some(input)),
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(format!("{}: {}", path.display(), error))
+            }
+            Err(_) => {}
+        }
+    }
+    let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    let verb = if paths.len() == 1 { "is" } else { "are" };
+    skip(&format!("{} {} missing", paths.join(" and "), verb));
+    Ok(None)
+}
+
+// Test output is captured by the print macros only, so writing to stderr
+// directly keeps the message visible without --nocapture.
+fn skip(reason: &str) {
+    let _ = writeln!(io::stderr(), "skipped: {}", reason);
+}
+
+/// Runs `solve` on the named input and checks its answer, skipping the check
+/// when the input is not available.
+pub fn check<T: PartialEq + Debug>(
+    inputs_dir: &str,
+    day: u32,
+    name: &str,
+    solve: impl FnOnce(&str) -> T,
+    expected: T,
+) {
+    let input = match load(inputs_dir, day, name) {
+        Ok(Some(input)) => input,
+        Ok(None) => return,
+        Err(error) => panic!("{}", error),
+    };
+    let result = solve(&input);
+    println!("{:?}", result);
+    assert_eq!(result, expected);
+}
+
+/// Runs `solve` on every example for `part`, checking their answers.
+pub fn check_examples<T: ToString>(examples: &[Example], part: u32, solve: impl Fn(&str) -> T) {
+    let examples: Vec<&Example> = examples.iter().filter(|e| e.part == part).collect();
+    assert!(!examples.is_empty(), "no examples for part {}", part);
+    for example in examples {
+        let result = solve(example.input).to_string();
+        println!("{}", result);
+        assert_eq!(
+            result,
+            example.answer.to_string(),
+            "example:\n{}",
+            example.input
+        );
+    }
+}
+
+/// Generates one test per row, each running a solution on an input from the
+/// crate's `inputs` directory and comparing its answer, such as
+///
+/// ```ignore
+/// aoc_core::answer_tests! {
+///     day 6;
+///     part1_example_input: part1(example) == 288;
+///     part1_actual_input: part1(actual) == 140220;
+/// }
+/// ```
+///
+/// where `example` reads `inputs/example.txt`. Tests whose input is missing
+/// pass, printing that they were skipped.
+#[macro_export]
+macro_rules! answer_tests {
+    (day $day:literal; $($name:ident: $solve:ident($input:ident) == $expected:expr;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::testing::check(
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/inputs"),
+                    $day,
+                    stringify!($input),
+                    |input| $solve($crate::puzzle::FromInput::from_input(input)),
+                    $expected,
+                );
+            }
+        )*
+    };
+}
+
+/// Generates one test per row, each checking a solution against the
+/// embedded examples for a part, such as
+///
+/// ```ignore
+/// aoc_core::example_tests! {
+///     EXAMPLES;
+///     part1_examples: part1(1);
+/// }
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    ($examples:expr; $($name:ident: $solve:ident($part:literal);)*) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::testing::check_examples($examples, $part, |input| {
+                    $solve($crate::puzzle::FromInput::from_input(input))
+                });
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::puzzle::FromInput;
+
+    fn count_lines(input: Vec<String>) -> usize {
+        input.len()
+    }
+
+    fn inputs_dir(name: &str) -> String {
+        let dir = env::temp_dir().join(format!("aoc-testing-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("example.txt"), "a\nb\r\nc\n").unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    crate::answer_tests! {
+        day 1;
+        skips_missing_example: count_lines(example) == 0;
+    }
+
+    const EXAMPLES: &[Example] = &[
+        Example {
+            part: 1,
+            input: "a\nb",
+            answer: 2,
+        },
+        Example {
+            part: 1,
+            input: "a",
+            answer: 1,
+        },
+    ];
+
+    crate::example_tests! {
+        EXAMPLES;
+        checks_examples: count_lines(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no examples for part 2")]
+    pub fn requires_examples() {
+        check_examples(EXAMPLES, 2, |i| count_lines(FromInput::from_input(i)));
+    }
+
+    #[test]
+    pub fn checks_answers() {
+        check(
+            &inputs_dir("pass"),
+            1,
+            "example",
+            |i| count_lines(FromInput::from_input(i)),
+            3,
+        );
+        check(
+            &inputs_dir("string"),
+            1,
+            "example",
+            String::from_input,
+            "a\nb\nc".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "left: 3\n right: 4")]
+    pub fn fails_wrong_answers() {
+        check(
+            &inputs_dir("fail"),
+            1,
+            "example",
+            |i| count_lines(FromInput::from_input(i)),
+            4,
+        );
+    }
+
+    #[test]
+    pub fn load_reports_missing_inputs() {
+        assert_eq!(load(&inputs_dir("missing"), 1, "custom"), Ok(None));
+    }
+}
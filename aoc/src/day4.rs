@@ -0,0 +1,35 @@
+use day4::{
+    parse_scratchcards,
+    trace::{self, Overflow},
+};
+
+use super::{option_value, positional, read_input, usage};
+
+fn trace(args: &[String]) -> Result<String, String> {
+    let dot = args.iter().any(|a| a == "--dot");
+    let overflow = match option_value(args, "--overflow") {
+        None | Some("clamp") => Overflow::Clamp,
+        Some("wrap") => Overflow::Wrap,
+        Some("error") => Overflow::Error,
+        Some(other) => return Err(format!("unknown overflow mode \"{}\"", other)),
+    };
+    let path = *positional(args, &["--overflow"])
+        .first()
+        .ok_or_else(usage)?;
+
+    let input = read_input(path)?;
+    let scratchcards = parse_scratchcards(&input).map_err(|e| e.to_string())?;
+    let traces = trace::cascade(&scratchcards, overflow).map_err(|e| e.to_string())?;
+    if dot {
+        Ok(trace::render_dot(&traces))
+    } else {
+        Ok(trace::render_table(&traces))
+    }
+}
+
+pub fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(|a| a.as_str()) {
+        Some("trace") => trace(&args[1..]),
+        _ => Err(usage()),
+    }
+}
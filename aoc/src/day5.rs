@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use day5::{
+    almanac::Almanac, lowest_location, lowest_location_brute_force, seeds::SeedSpec, visualise,
+};
+
+use super::{option_value, positional, read_input, usage};
+
+fn parse_number(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| format!("invalid number \"{}\"", value))
+}
+
+fn map(args: &[String]) -> Result<String, String> {
+    let from = option_value(args, "--from").ok_or_else(usage)?;
+    let to = option_value(args, "--to").ok_or_else(usage)?;
+    let positional = positional(args, &["--from", "--to"]);
+    let (path, values) = positional.split_first().ok_or_else(usage)?;
+    if values.is_empty() {
+        return Err(usage());
+    }
+
+    let input = read_input(path)?;
+    let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+    let almanac = Almanac::parse(&lines).map_err(|e| e.to_string())?;
+
+    let mut output = String::new();
+    for value in values {
+        if let Some((start, end)) = value.split_once("..") {
+            let (start, end) = (parse_number(start)?, parse_number(end)?);
+            let steps = almanac
+                .convert_range(from, to, start, end)
+                .map_err(|e| e.to_string())?;
+            for (category, ranges) in steps {
+                let ranges: Vec<String> = ranges
+                    .iter()
+                    .map(|(start, end)| format!("{}..{}", start, end))
+                    .collect();
+                writeln!(output, "{}: {}", category, ranges.join(", ")).unwrap();
+            }
+        } else {
+            let steps = almanac
+                .convert(from, to, parse_number(value)?)
+                .map_err(|e| e.to_string())?;
+            let steps: Vec<String> = steps
+                .iter()
+                .map(|(category, value)| format!("{} {}", category, value))
+                .collect();
+            writeln!(output, "{}", steps.join(" -> ")).unwrap();
+        }
+    }
+    Ok(output)
+}
+
+const SEED_OPTIONS: [&str; 2] = ["--seeds", "--seed-ranges"];
+
+/// Reads `--seeds individual|start-length|start-end` or `--seed-ranges <file>`,
+/// where the file holds one `start..end` range per line.
+fn seed_spec(args: &[String], default: SeedSpec) -> Result<SeedSpec, String> {
+    if let Some(path) = option_value(args, "--seed-ranges") {
+        let ranges = read_input(path)?
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                let (start, end) = l
+                    .trim()
+                    .split_once("..")
+                    .ok_or_else(|| format!("invalid seed range \"{}\"", l))?;
+                Ok((parse_number(start)?, parse_number(end)?))
+            })
+            .collect::<Result<_, String>>()?;
+        return Ok(SeedSpec::Ranges(ranges));
+    }
+    match option_value(args, "--seeds") {
+        None => Ok(default),
+        Some("individual") => Ok(SeedSpec::Individual),
+        Some("start-length") => Ok(SeedSpec::StartLength),
+        Some("start-end") => Ok(SeedSpec::StartEnd),
+        Some(other) => Err(format!("unknown seed interpretation \"{}\"", other)),
+    }
+}
+
+fn lowest(args: &[String]) -> Result<String, String> {
+    let seeds = seed_spec(args, SeedSpec::Individual)?;
+    let brute_force = args.iter().any(|a| a == "--brute-force");
+    let threads = match option_value(args, "--threads") {
+        Some(threads) => parse_number(threads)? as usize,
+        None => 0,
+    };
+    let options: Vec<&str> = SEED_OPTIONS.iter().copied().chain(["--threads"]).collect();
+    let path = *positional(args, &options).first().ok_or_else(usage)?;
+
+    let input = read_input(path)?;
+    let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+    let lowest = if brute_force {
+        lowest_location_brute_force(lines, &seeds, threads)
+    } else {
+        lowest_location(lines, &seeds)
+    };
+    Ok(format!("{}\n", lowest.map_err(|e| e.to_string())?))
+}
+
+fn fragments(args: &[String]) -> Result<String, String> {
+    let svg = args.iter().any(|a| a == "--svg");
+    let seeds = seed_spec(args, SeedSpec::StartLength)?;
+    let path = *positional(args, &SEED_OPTIONS).first().ok_or_else(usage)?;
+
+    let input = read_input(path)?;
+    let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+    let almanac = Almanac::parse(&lines).map_err(|e| e.to_string())?;
+    let intervals = seeds.intervals(&almanac.seeds).map_err(|e| e.to_string())?;
+    let fragments = visualise::fragments(&almanac, &intervals).map_err(|e| e.to_string())?;
+    if svg {
+        Ok(visualise::render_svg(&fragments))
+    } else {
+        Ok(visualise::render_tree(&fragments))
+    }
+}
+
+pub fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(|a| a.as_str()) {
+        Some("map") => map(&args[1..]),
+        Some("lowest") => lowest(&args[1..]),
+        Some("fragments") => fragments(&args[1..]),
+        _ => Err(usage()),
+    }
+}
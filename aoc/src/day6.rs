@@ -0,0 +1,25 @@
+use day6::{outcome, Races};
+
+use super::{positional, read_input, usage};
+
+fn races(args: &[String]) -> Result<String, String> {
+    let kerned = args.iter().any(|a| a == "--kerned");
+    let path = *positional(args, &[]).first().ok_or_else(usage)?;
+
+    let input = read_input(path)?;
+    let races: Races = input
+        .parse()
+        .map_err(|e: day6::ParseRacesError| e.to_string())?;
+    if kerned {
+        Ok(outcome::render_table(&[races.kerned()]))
+    } else {
+        Ok(outcome::render_table(&races.columns()))
+    }
+}
+
+pub fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(|a| a.as_str()) {
+        Some("races") => races(&args[1..]),
+        _ => Err(usage()),
+    }
+}
@@ -0,0 +1,101 @@
+use std::{env, fs, process};
+
+use aoc_core::inputs::Inputs;
+
+mod day4;
+mod day5;
+mod day6;
+mod run;
+
+fn usage() -> String {
+    [
+        "Usage: aoc <command> [options]",
+        "",
+        "Commands:",
+        "    run <day> [--example | <input>]",
+        "        Solve both parts on the examples, an input file or the cached input",
+        "    fetch <day>...",
+        "        Download puzzle inputs into the cache, printing where they are",
+        "    day4 trace [--dot] [--overflow clamp|wrap|error] <input>",
+        "        Show how the part 2 copy cascade plays out",
+        "    day5 map --from <category> --to <category> <input> <value|start..end>...",
+        "        Convert numbers or ranges between almanac categories",
+        "    day5 lowest [<seeds>] [--brute-force [--threads <n>]] <input>",
+        "        Find the lowest location of any seed, optionally by converting",
+        "        every seed on <n> threads (default one per core)",
+        "    day5 fragments [--svg] [<seeds>] <input>",
+        "        Show how seed ranges fragment on their way to locations",
+        "    day6 races [--kerned] <input>",
+        "        Show the winning hold times of every race",
+        "",
+        "    <seeds> is --seeds individual|start-length|start-end, or",
+        "    --seed-ranges <file> with one start..end range per line",
+        "",
+        "Inputs are cached in AOC_CACHE_DIR (default advent-of-code-2023 in",
+        "$XDG_CACHE_HOME, ~/.cache or %LOCALAPPDATA%) and downloaded from",
+        "AOC_BASE_URL using the AOC_SESSION cookie.",
+    ]
+    .join("\n")
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))
+}
+
+/// Value following `--name` in `args`, if given.
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|a| a == name)?;
+    args.get(index + 1).map(|a| a.as_str())
+}
+
+/// Arguments that are neither flags nor the values of `options_with_values`.
+fn positional<'a>(args: &'a [String], options_with_values: &[&str]) -> Vec<&'a str> {
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if options_with_values.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+fn fetch(args: &[String]) -> Result<String, String> {
+    if args.is_empty() {
+        return Err(usage());
+    }
+    let inputs = Inputs::from_env();
+    let mut output = String::new();
+    for day in args {
+        let day = day
+            .parse::<u32>()
+            .map_err(|_| format!("invalid day \"{}\"", day))?;
+        inputs.get(day).map_err(|e| e.to_string())?;
+        output.push_str(&format!("{}\n", inputs.path(day).display()));
+    }
+    Ok(output)
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(|a| a.as_str()) {
+        Some("run") => run::run(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("day4") => day4::run(&args[1..]),
+        Some("day5") => day5::run(&args[1..]),
+        Some("day6") => day6::run(&args[1..]),
+        _ => Err(usage()),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => print!("{}", output),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
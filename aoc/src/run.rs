@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use aoc_core::{
+    inputs::Inputs,
+    puzzle::{Example, FromInput},
+};
+
+use super::{positional, read_input, usage};
+
+fn answer<T: FromInput, A: ToString>(solve: fn(T) -> A, input: &str) -> String {
+    solve(T::from_input(input)).to_string()
+}
+
+fn solve(day: u32, part: u32, input: &str) -> Option<String> {
+    Some(match (day, part) {
+        (1, 1) => answer(day1::part1, input),
+        (1, 2) => answer(day1::part2, input),
+        (2, 1) => answer(day2::part1, input),
+        (2, 2) => answer(day2::part2, input),
+        (3, 1) => answer(day3::part1, input),
+        (3, 2) => answer(day3::part2, input),
+        (4, 1) => answer(day4::part1, input),
+        (4, 2) => answer(day4::part2, input),
+        (5, 1) => answer(day5::part1, input),
+        (5, 2) => answer(day5::part2, input),
+        (6, 1) => answer(day6::part1, input),
+        (6, 2) => answer(day6::part2, input),
+        _ => return None,
+    })
+}
+
+fn examples(day: u32) -> Option<&'static [Example]> {
+    match day {
+        1 => Some(day1::EXAMPLES),
+        2 => Some(day2::EXAMPLES),
+        3 => Some(day3::EXAMPLES),
+        4 => Some(day4::EXAMPLES),
+        5 => Some(day5::EXAMPLES),
+        6 => Some(day6::EXAMPLES),
+        _ => None,
+    }
+}
+
+/// Solves both parts of a day, on its examples with `--example`, or else on
+/// the given input file or the cached input.
+pub fn run(args: &[String]) -> Result<String, String> {
+    let day = args.first().ok_or_else(usage)?;
+    let day = day
+        .parse::<u32>()
+        .map_err(|_| format!("invalid day \"{}\"", day))?;
+    let examples = examples(day).ok_or_else(|| format!("day {} is not solved yet", day))?;
+
+    let mut output = String::new();
+    if args.iter().any(|a| a == "--example") {
+        let mut wrong = 0;
+        for example in examples {
+            let result = solve(day, example.part, example.input).unwrap();
+            let verdict = if result == example.answer.to_string() {
+                "ok"
+            } else {
+                wrong += 1;
+                "WRONG"
+            };
+            writeln!(
+                output,
+                "part {}: {} (expected {}, {})",
+                example.part, result, example.answer, verdict
+            )
+            .unwrap();
+        }
+        if wrong > 0 {
+            return Err(format!("{}{} example answers are wrong", output, wrong));
+        }
+        return Ok(output);
+    }
+
+    let input = match positional(&args[1..], &[]).first() {
+        Some(path) => read_input(path)?,
+        None => Inputs::from_env().get(day).map_err(|e| e.to_string())?,
+    };
+    for part in [1, 2] {
+        writeln!(
+            output,
+            "part {}: {}",
+            part,
+            solve(day, part, &input).unwrap()
+        )
+        .unwrap();
+    }
+    Ok(output)
+}
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 
+use aoc_core::puzzle::Example;
 use regex::Regex;
 
-fn part1(input: String) -> u32 {
+pub fn part1(input: String) -> u32 {
     let mut sum: u32 = 0;
     for line in input.lines() {
         let mut digits = Vec::new();
@@ -19,7 +20,7 @@ fn part1(input: String) -> u32 {
     return sum;
 }
 
-fn part2(input: String) -> u32 {
+pub fn part2(input: String) -> u32 {
     fn get_digit(line: &str, regex: &Regex, mappings: &HashMap<String, u32>) -> u32 {
         let first_group = regex.captures(line).unwrap().get(1);
         let parsed_string = first_group.unwrap().as_str();
@@ -80,61 +81,46 @@ fn part2(input: String) -> u32 {
     return sum;
 }
 
+const PART1_EXAMPLE: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+const PART2_EXAMPLE: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: PART1_EXAMPLE,
+        answer: 142,
+    },
+    Example {
+        part: 2,
+        input: PART2_EXAMPLE,
+        answer: 281,
+    },
+];
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
     use super::*;
 
-    fn inputs_path() -> &'static str {
-        "inputs"
-    }
-
-    fn example_input_path() -> String {
-        format!("{}/example.txt", inputs_path())
-    }
-
-    fn actual_input_path() -> String {
-        format!("{}/actual.txt", inputs_path())
-    }
-
-    fn custom_input_path() -> String {
-        format!("{}/custom.txt", inputs_path())
-    }
-
-    #[test]
-    fn part1_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 55017);
-    }
-
-    #[test]
-    fn part2_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 281);
-    }
-
-    #[test]
-    fn part2_custom_input() {
-        let file_content = fs::read_to_string(custom_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 195);
+    aoc_core::example_tests! {
+        EXAMPLES;
+        part1_examples: part1(1);
+        part2_examples: part2(2);
     }
 
-    #[test]
-    fn part2_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 53539);
+    aoc_core::answer_tests! {
+        day 1;
+        part1_actual_input: part1(actual) == 55017;
+        part2_custom_input: part2(custom) == 195;
+        part2_actual_input: part2(actual) == 53539;
     }
 }
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use super::{get_content, get_rounds, minimum_cubes};
+
+// Every round the elf grabs a handful of cubes from the bag, shows them and puts
+// them back, so each round is an independent draw without replacement and the
+// chance of seeing it follows the multivariate hypergeometric distribution.
+
+#[derive(Debug)]
+pub struct BagEstimate {
+    pub bag: HashMap<String, u32>,
+    pub lower_bounds: HashMap<String, u32>,
+    pub likelihood: f64,
+}
+
+fn ln_choose(n: u32, k: u32) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+fn round_log_likelihood(round: &HashMap<String, u32>, bag: &HashMap<String, u32>) -> f64 {
+    let total_in_bag: u32 = bag.values().sum();
+    let total_drawn: u32 = round.values().sum();
+    if total_drawn > total_in_bag {
+        return f64::NEG_INFINITY;
+    }
+
+    let mut log_likelihood = -ln_choose(total_in_bag, total_drawn);
+    for (color, drawn) in round {
+        let in_bag = *bag.get(color).unwrap_or(&0);
+        log_likelihood += ln_choose(in_bag, *drawn);
+    }
+    log_likelihood
+}
+
+fn log_likelihood(rounds: &[HashMap<String, u32>], bag: &HashMap<String, u32>) -> f64 {
+    rounds
+        .iter()
+        .map(|round| round_log_likelihood(round, bag))
+        .sum()
+}
+
+/// Probability of observing every round of `game` (a full "Game N: ..." line)
+/// when the cubes are drawn from `bag`. Impossible games have a likelihood of 0.
+pub fn game_likelihood(game: &str, bag: &HashMap<String, u32>) -> f64 {
+    let game_content = get_content(game);
+    log_likelihood(&get_rounds(&game_content), bag).exp()
+}
+
+/// Searches every bag holding at least the minimum number of cubes needed for
+/// `game` and at most `max_cubes_per_color` of each color, returning the one
+/// under which the game is most likely. Ties go to the smallest bag.
+pub fn most_likely_bag(game: &str, max_cubes_per_color: u32) -> BagEstimate {
+    let game_content = get_content(game);
+    let rounds = get_rounds(&game_content);
+    let lower_bounds = minimum_cubes(&game_content);
+
+    let mut colors: Vec<&String> = lower_bounds.keys().collect();
+    colors.sort();
+    let lower: Vec<u32> = colors.iter().map(|c| lower_bounds[*c]).collect();
+    let upper: Vec<u32> = lower.iter().map(|&l| l.max(max_cubes_per_color)).collect();
+
+    let mut best_bag = lower.clone();
+    let mut best_log_likelihood = f64::NEG_INFINITY;
+    let mut current = lower.clone();
+    loop {
+        let bag: HashMap<String, u32> = colors
+            .iter()
+            .map(|c| c.to_string())
+            .zip(current.iter().copied())
+            .collect();
+        let candidate = log_likelihood(&rounds, &bag);
+        if candidate > best_log_likelihood {
+            best_log_likelihood = candidate;
+            best_bag = current.clone();
+        }
+
+        // Advance to the next bag, odometer style
+        let mut index = 0;
+        while index < current.len() && current[index] == upper[index] {
+            current[index] = lower[index];
+            index += 1;
+        }
+        if index == current.len() {
+            break;
+        }
+        current[index] += 1;
+    }
+
+    let bag = colors.iter().map(|c| c.to_string()).zip(best_bag).collect();
+    BagEstimate {
+        bag,
+        lower_bounds,
+        likelihood: best_log_likelihood.exp(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bag(red: u32, green: u32, blue: u32) -> HashMap<String, u32> {
+        HashMap::from([
+            ("red".to_string(), red),
+            ("green".to_string(), green),
+            ("blue".to_string(), blue),
+        ])
+    }
+
+    #[test]
+    fn likelihood_of_single_draw() {
+        // Drawing 1 red out of 1 red and 1 blue
+        let likelihood = game_likelihood("Game 1: 1 red", &bag(1, 0, 1));
+        assert!((likelihood - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn likelihood_of_impossible_game() {
+        let game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        assert_eq!(game_likelihood(game, &bag(12, 13, 14)), 0.0);
+    }
+
+    #[test]
+    fn likelihood_of_round_drawing_more_than_the_bag_holds() {
+        assert_eq!(game_likelihood("Game 1: 2 red, 1 blue", &bag(1, 0, 1)), 0.0);
+    }
+
+    #[test]
+    fn most_likely_bag_respects_lower_bounds() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let estimate = most_likely_bag(game, 10);
+        assert_eq!(estimate.lower_bounds, bag(4, 2, 6));
+        for (color, amount) in &estimate.bag {
+            assert!(*amount >= estimate.lower_bounds[color]);
+        }
+        assert!(estimate.likelihood > 0.0);
+        assert!(estimate.likelihood >= game_likelihood(game, &bag(4, 2, 6)));
+    }
+
+    #[test]
+    fn most_likely_bag_for_single_color() {
+        let estimate = most_likely_bag("Game 1: 2 red; 2 red", 5);
+        assert_eq!(estimate.bag, bag(2, 0, 0));
+        assert!((estimate.likelihood - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,41 +1,71 @@
 use std::{cmp, collections::HashMap};
 
-fn get_id(line: &String) -> u32 {
-    let l_index = line.find("Game ").unwrap() + 5;
-    let r_index = line.find(":").unwrap();
-    return line[l_index..r_index].to_string().parse::<u32>().unwrap();
+use aoc_core::parse::{
+    literal, map, pair, parse_line, preceded, rest, separated, spaced, terminated, unsigned, word,
+    PResult,
+};
+use aoc_core::puzzle::Example;
+
+pub mod inference;
+
+fn game_id<'a>() -> impl Fn(&'a str) -> PResult<'a, u32> {
+    preceded(
+        spaced(literal("Game")),
+        terminated(spaced(unsigned()), spaced(literal(":"))),
+    )
 }
 
-fn get_content(line: &String) -> String {
-    let cubes_start = line.find(":").unwrap() + 1;
-    let game_content = line[cubes_start..].trim().to_string();
-    return game_content;
+fn rounds<'a>() -> impl Fn(&'a str) -> PResult<'a, Vec<HashMap<String, u32>>> {
+    let cubes = pair(spaced(unsigned::<u32>()), spaced(word()));
+    let round = map(separated(cubes, spaced(literal(","))), |cubes| {
+        let mut round: HashMap<String, u32> = HashMap::new();
+        for (amount, color) in cubes {
+            *round.entry(color.to_string()).or_insert(0) += amount;
+        }
+        round
+    });
+    separated(round, spaced(literal(";")))
+}
+
+fn get_id(line: &str) -> u32 {
+    parse_line(terminated(game_id(), rest()), line).unwrap()
 }
 
-fn get_amount_color_pair_from_subset(subset: &String) -> (u32, &str) {
-    let trimmed = subset.trim();
-    let separator_index = trimmed.find(' ').unwrap();
-    let amount = &trimmed[..separator_index].parse::<u32>().unwrap();
-    let color = &trimmed[separator_index + 1..];
-    return (*amount, color);
+fn get_content(line: &str) -> String {
+    let game_content = parse_line(preceded(game_id(), rest()), line).unwrap();
+    game_content.trim().to_string()
 }
 
-fn part1(input: String) -> u32 {
-    fn is_game_possible(game: &String, max_cubes: &HashMap<String, u32>) -> bool {
-        let sets = game.split(';');
-        for subset in sets {
-            let subsets = subset.split(',');
-            for cube in subsets {
-                let cube_string = &cube.to_string();
-                let (amount, color) = get_amount_color_pair_from_subset(cube_string);
+fn get_rounds(game: &str) -> Vec<HashMap<String, u32>> {
+    parse_line(rounds(), game).unwrap()
+}
 
-                let max = max_cubes.get(color).unwrap();
+fn minimum_cubes(game: &str) -> HashMap<String, u32> {
+    let mut minimum_cubes_needed: HashMap<String, u32> = HashMap::from([
+        ("red".to_string(), 0),
+        ("green".to_string(), 0),
+        ("blue".to_string(), 0),
+    ]);
+    for round in get_rounds(game) {
+        for (color, amount) in round {
+            let current_cubes = minimum_cubes_needed.entry(color).or_insert(0);
+            *current_cubes = cmp::max(*current_cubes, amount);
+        }
+    }
+    minimum_cubes_needed
+}
+
+pub fn part1(input: String) -> u32 {
+    fn is_game_possible(game: &str, max_cubes: &HashMap<String, u32>) -> bool {
+        for round in get_rounds(game) {
+            for (color, amount) in round {
+                let max = max_cubes.get(&color).unwrap();
                 if amount > *max {
                     return false;
                 }
             }
         }
-        return true;
+        true
     }
 
     let max_cubes_allowed: HashMap<String, u32> = HashMap::from([
@@ -58,90 +88,50 @@ fn part1(input: String) -> u32 {
     return sum;
 }
 
-fn part2(input: String) -> u32 {
-    fn minimum_cubes(game: &String) -> u32 {
-        let mut minimum_cubes_needed: HashMap<String, u32> = HashMap::from([
-            ("red".to_string(), 0),
-            ("green".to_string(), 0),
-            ("blue".to_string(), 0),
-        ]);
-        let sets = game.split(';');
-        for subset in sets {
-            let subsets = subset.split(',');
-            for cube in subsets {
-                let cube_string = &cube.to_string();
-                let (amount, color) = get_amount_color_pair_from_subset(cube_string);
-
-                let current_cubes = minimum_cubes_needed.get(color).unwrap();
-                let new_minimum = cmp::max(current_cubes, &amount);
-                minimum_cubes_needed.insert(color.to_string(), *new_minimum);
-            }
-        }
-        return minimum_cubes_needed.values().clone().product();
-    }
-
+pub fn part2(input: String) -> u32 {
     let mut sum: u32 = 0;
     for line in input.lines() {
         let line_string = line.to_string();
         let game_content = get_content(&line_string);
 
-        let minimum = minimum_cubes(&game_content);
+        let minimum: u32 = minimum_cubes(&game_content).values().product();
         sum += minimum;
     }
     return sum;
 }
 
+const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE,
+        answer: 8,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE,
+        answer: 2286,
+    },
+];
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
     use super::*;
 
-    fn inputs_path() -> &'static str {
-        "inputs"
-    }
-
-    fn example_input_path() -> String {
-        format!("{}/example.txt", inputs_path())
-    }
-
-    fn actual_input_path() -> String {
-        format!("{}/actual.txt", inputs_path())
-    }
-
-    #[test]
-    fn part1_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 2528);
-    }
-
-    #[test]
-    fn part1_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 8);
-    }
-
-    #[test]
-    fn part2_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 67363);
+    aoc_core::example_tests! {
+        EXAMPLES;
+        part1_examples: part1(1);
+        part2_examples: part2(2);
     }
 
-    #[test]
-    fn part2_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 2286);
+    aoc_core::answer_tests! {
+        day 2;
+        part1_actual_input: part1(actual) == 2528;
+        part2_actual_input: part2(actual) == 67363;
     }
 }
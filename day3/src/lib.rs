@@ -1,5 +1,7 @@
 use std::collections::{BTreeMap, HashSet};
 
+use aoc_core::puzzle::Example;
+
 fn get_surrounding_points(top_left: &(i32, i32), bottom_right: &(i32, i32)) -> HashSet<(i32, i32)> {
     let mut points = HashSet::new();
     for y in top_left.0..=bottom_right.0 {
@@ -51,7 +53,7 @@ fn get_number_positions(schematic: &Vec<Vec<char>>) -> BTreeMap<(u32, u32), Stri
     number_index_lengths
 }
 
-fn part1(input: String) -> u32 {
+pub fn part1(input: String) -> u32 {
     fn is_next_to_symbol(
         start_index: &(u32, u32),
         number: &String,
@@ -96,17 +98,29 @@ fn part1(input: String) -> u32 {
 
     let mut sum: u32 = 0;
     for (index, digits) in &number_index_lengths {
-        println!("{},{}: {}", index.0, index.1, digits);
-        let next_to = is_next_to_symbol(index, digits, &schematic, &symbols);
-        println!("{}", next_to);
-        if next_to {
+        if is_next_to_symbol(index, digits, &schematic, &symbols) {
             sum += digits.parse::<u32>().clone().unwrap();
         }
     }
     sum
 }
 
-fn part2(input: String) -> i32 {
+/// A `*` next to exactly two part numbers.
+#[derive(Debug, PartialEq)]
+pub struct Gear {
+    pub y: usize,
+    pub x: usize,
+    pub parts: (i32, i32),
+}
+
+impl Gear {
+    pub fn ratio(&self) -> i32 {
+        self.parts.0 * self.parts.1
+    }
+}
+
+/// Finds the gears of the schematic, from top to bottom and left to right.
+pub fn gears(input: &str) -> Vec<Gear> {
     let mut schematic: Vec<Vec<char>> = Vec::new();
     for line in input.lines() {
         let mut row: Vec<char> = Vec::new();
@@ -128,7 +142,7 @@ fn part2(input: String) -> i32 {
         ));
     }
 
-    let mut sum: i32 = 0;
+    let mut gears = Vec::new();
     for y in 0..schematic.len() {
         let row = schematic.get(y).unwrap();
         for x in 0..row.len() {
@@ -144,106 +158,93 @@ fn part2(input: String) -> i32 {
                         intersections_values.push(key);
                     }
                 }
-                if intersections_values.len() == 2 {
-                    println!(
-                        "Gear at {},{} had intersection of {} and {}",
+                if let [a, b] = intersections_values[..] {
+                    gears.push(Gear {
                         y,
                         x,
-                        intersections_values.get(0).unwrap(),
-                        intersections_values.get(1).unwrap()
-                    );
-                    sum += intersections_values.clone().into_iter().product::<i32>();
+                        parts: (a, b),
+                    });
                 }
             }
         }
     }
-    sum
+    gears
 }
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
+/// One line per gear, with its position, part numbers and ratio.
+pub fn render_gears(gears: &[Gear]) -> String {
+    gears
+        .iter()
+        .map(|g| {
+            format!(
+                "Gear at {},{} had intersection of {} and {} (ratio {})\n",
+                g.y,
+                g.x,
+                g.parts.0,
+                g.parts.1,
+                g.ratio()
+            )
+        })
+        .collect()
+}
 
-    use super::*;
+pub fn part2(input: String) -> i32 {
+    gears(&input).iter().map(Gear::ratio).sum()
+}
 
-    fn inputs_path() -> &'static str {
-        "inputs"
-    }
+const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
 
-    fn example_input_path() -> String {
-        format!("{}/example.txt", inputs_path())
-    }
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE,
+        answer: 4361,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE,
+        answer: 467835,
+    },
+];
 
-    fn actual_input_path() -> String {
-        format!("{}/actual.txt", inputs_path())
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[test]
-    fn part1_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content
-            .lines()
-            .map(|line| line.trim())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 533775);
+    aoc_core::example_tests! {
+        EXAMPLES;
+        part1_examples: part1(1);
+        part2_examples: part2(2);
     }
 
     #[test]
-    fn part1_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content
-            .lines()
-            .map(|line| line.trim())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 4361);
+    fn gears_snapshot() {
+        aoc_core::assert_snapshot!("gears_example", render_gears(&gears(EXAMPLE)));
     }
 
-    #[test]
-    fn part2_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content
-            .lines()
-            .map(|line| line.trim())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 78236071);
+    aoc_core::answer_tests! {
+        day 3;
+        part1_actual_input: part1(actual) == 533775;
+        part2_actual_input: part2(actual) == 78236071;
     }
 
     #[test]
-    fn part2_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content
-            .lines()
-            .map(|line| line.trim())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 467835);
+    fn part1_checked_in_actual_input() {
+        assert_eq!(part1(actual_input()), 533775);
     }
 
-    fn example_input() -> String {
-        "
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..
-        "
-        .to_string()
+    #[test]
+    fn part2_checked_in_actual_input() {
+        assert_eq!(part2(actual_input()), 78236071);
     }
 
     fn actual_input() -> String {
@@ -1,18 +1,52 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::HashSet, error::Error, fmt, str::FromStr};
 
-struct Scratchcard {
+use aoc_core::numbers::Numbers;
+use aoc_core::parse::{
+    literal, numbers, pair, parse_line, preceded, spaced, terminated, unsigned, PResult, ParseError,
+};
+use aoc_core::puzzle::Example;
+
+pub mod scoring;
+pub mod trace;
+
+use scoring::ScoringRule;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseScratchcardError {
+    Syntax(ParseError),
+    DuplicateNumber { card: u32, number: u32 },
+    NonSequentialId { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ParseScratchcardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => write!(f, "{}", error),
+            Self::DuplicateNumber { card, number } => {
+                write!(f, "card {} lists number {} more than once", card, number)
+            }
+            Self::NonSequentialId { expected, found } => {
+                write!(f, "expected card {} but found card {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for ParseScratchcardError {}
+
+pub struct Scratchcard {
+    id: u32,
     winning_numbers: HashSet<u32>,
     numbers_you_have: HashSet<u32>,
 }
 
 impl Scratchcard {
-    fn points(&self) -> u32 {
-        let matches: u32 = self.matches().len().try_into().unwrap();
-        if matches > 0 {
-            2_u32.pow(matches - 1)
-        } else {
-            0
-        }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn points(&self, rule: &ScoringRule) -> Option<u64> {
+        rule.score(self.matches().len().try_into().ok()?)
     }
 
     fn matches(&self) -> HashSet<&u32> {
@@ -22,120 +56,183 @@ impl Scratchcard {
     }
 }
 
-fn parse_scratchcard(input: &str) -> Scratchcard {
-    let (colon_index, _) = input.char_indices().nth(input.find(':').unwrap()).unwrap();
-    let (bar_index, _) = input.char_indices().nth(input.find('|').unwrap()).unwrap();
+fn unique_numbers(card: u32, numbers: Numbers<u32>) -> Result<HashSet<u32>, ParseScratchcardError> {
+    let mut unique = HashSet::new();
+    // The parser has already checked every number
+    for number in numbers.flatten() {
+        if !unique.insert(number) {
+            return Err(ParseScratchcardError::DuplicateNumber { card, number });
+        }
+    }
+    Ok(unique)
+}
 
-    let winning_numbers_str = &input[colon_index + 2..bar_index - 1];
-    let numbers_you_have_str = &input[bar_index + 2..];
+/// Winning numbers and numbers you have, as written on a card.
+type CardNumbers<'a> = (Numbers<'a, u32>, Numbers<'a, u32>);
+
+/// `Card <id>: <winning numbers> | <numbers you have>`
+fn scratchcard<'a>() -> impl Fn(&'a str) -> PResult<'a, (u32, CardNumbers<'a>)> {
+    let id = preceded(
+        spaced(literal("Card")),
+        terminated(spaced(unsigned()), spaced(literal(":"))),
+    );
+    let numbers = pair(terminated(numbers(), spaced(literal("|"))), numbers());
+    pair(id, numbers)
+}
 
-    let winning_numbers: HashSet<u32> = winning_numbers_str
-        .split_whitespace()
-        .map(|n| n.trim().parse::<u32>().unwrap())
-        .collect();
+impl FromStr for Scratchcard {
+    type Err = ParseScratchcardError;
 
-    let numbers_you_have: HashSet<u32> = numbers_you_have_str
-        .split_whitespace()
-        .map(|n| n.trim().parse::<u32>().unwrap())
-        .collect();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, (winning_numbers, numbers_you_have)) =
+            parse_line(scratchcard(), s).map_err(ParseScratchcardError::Syntax)?;
 
-    Scratchcard {
-        winning_numbers,
-        numbers_you_have,
+        Ok(Scratchcard {
+            id,
+            winning_numbers: unique_numbers(id, winning_numbers)?,
+            numbers_you_have: unique_numbers(id, numbers_you_have)?,
+        })
     }
 }
 
-pub fn part1(input: String) -> u32 {
-    let scratchcards: Vec<Scratchcard> =
-        input.lines().map(|line| parse_scratchcard(line)).collect();
-    scratchcards.into_iter().map(|s| s.points()).sum()
+/// Parses one scratchcard per non-empty line, checking that the cards are
+/// numbered 1, 2, 3, ... in order.
+pub fn parse_scratchcards(input: &str) -> Result<Vec<Scratchcard>, ParseScratchcardError> {
+    let mut scratchcards = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let scratchcard: Scratchcard = line.parse().map_err(|e| match e {
+            ParseScratchcardError::Syntax(e) => ParseScratchcardError::Syntax(e.on_line(index + 1)),
+            e => e,
+        })?;
+        let expected = scratchcards.len() as u32 + 1;
+        if scratchcard.id != expected {
+            return Err(ParseScratchcardError::NonSequentialId {
+                expected,
+                found: scratchcard.id,
+            });
+        }
+        scratchcards.push(scratchcard);
+    }
+    Ok(scratchcards)
 }
 
-pub fn part2(input: String) -> u32 {
-    let original_scratchcards: Vec<Scratchcard> =
-        input.lines().map(|line| parse_scratchcard(line)).collect();
-
-    let mut all_scratchcards = HashMap::new();
-    for original_card_number in 1..=original_scratchcards.len() {
-        let original_card =
-            Vec::from([original_scratchcards.get(original_card_number - 1).unwrap()]);
-        all_scratchcards.insert(original_card_number, original_card);
-    }
+/// Total points of all scratchcards under `rule`, or `None` if it overflows.
+pub fn total_points(scratchcards: &[Scratchcard], rule: &ScoringRule) -> Option<u64> {
+    scratchcards
+        .iter()
+        .try_fold(0_u64, |sum, s| sum.checked_add(s.points(rule)?))
+}
 
-    for current_card_number in 1..=original_scratchcards.len() {
-        let original_card = original_scratchcards.get(current_card_number - 1).unwrap();
-        let copies_of_original_card = all_scratchcards.get_mut(&current_card_number).unwrap();
-        let copies_to_win = copies_of_original_card.len();
-        if copies_to_win > 0 {
-            for next_card_number in
-                current_card_number + 1..=current_card_number + original_card.matches().len()
-            {
-                for _ in 0..copies_to_win {
-                    let copies_of_next_card = all_scratchcards.get_mut(&next_card_number).unwrap();
-                    copies_of_next_card
-                        .push(original_scratchcards.get(next_card_number - 1).unwrap());
-                }
-            }
-        }
-    }
+pub fn part1(input: String) -> u64 {
+    let scratchcards = parse_scratchcards(&input).unwrap();
+    total_points(&scratchcards, &ScoringRule::Doubling).unwrap()
+}
 
-    all_scratchcards
-        .values()
-        .map(|v| TryInto::<u32>::try_into(v.len()).unwrap())
+pub fn part2(input: String) -> u32 {
+    let original_scratchcards = parse_scratchcards(&input).unwrap();
+    trace::cascade(&original_scratchcards, trace::Overflow::Clamp)
+        .unwrap()
+        .iter()
+        .map(|t| t.copies)
         .sum()
 }
 
+const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE,
+        answer: 13,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE,
+        answer: 30,
+    },
+];
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
     use super::*;
 
-    fn inputs_path() -> &'static str {
-        "inputs"
+    #[test]
+    pub fn parse_keeps_card_ids() {
+        let scratchcards = parse_scratchcards(EXAMPLE).unwrap();
+        let ids: Vec<u32> = scratchcards.iter().map(|s| s.id()).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(scratchcards[2].matches().len(), 2);
     }
 
-    fn example_input_path() -> String {
-        format!("{}/example.txt", inputs_path())
+    #[test]
+    pub fn parse_tolerates_whitespace() {
+        let scratchcard: Scratchcard = "  Card   12 :41 48\t83|  83   86 48  ".parse().unwrap();
+        assert_eq!(scratchcard.id(), 12);
+        assert_eq!(scratchcard.winning_numbers, HashSet::from([41, 48, 83]));
+        assert_eq!(scratchcard.numbers_you_have, HashSet::from([83, 86, 48]));
     }
 
-    fn actual_input_path() -> String {
-        format!("{}/actual.txt", inputs_path())
+    #[test]
+    pub fn parse_reports_where_a_card_is_malformed() {
+        let result = parse_scratchcards("Card 1: 1 | 2\n\nCard 2: 3 4 5");
+        let error = match result.err() {
+            Some(ParseScratchcardError::Syntax(error)) => error,
+            other => panic!(
+                "expected a syntax error, got {:?}",
+                other.map(|e| e.to_string())
+            ),
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 14: expected \"|\", found end of line"
+        );
     }
 
     #[test]
-    pub fn part1_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 13);
+    pub fn parse_rejects_duplicate_numbers() {
+        let result = "Card 3: 1 2 3 | 4 5 4".parse::<Scratchcard>();
+        assert_eq!(
+            result.err(),
+            Some(ParseScratchcardError::DuplicateNumber { card: 3, number: 4 })
+        );
     }
 
     #[test]
-    pub fn part1_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 26426);
+    pub fn parse_rejects_non_sequential_ids() {
+        let result = parse_scratchcards("Card 1: 1 | 2\nCard 3: 3 | 4");
+        assert_eq!(
+            result.err(),
+            Some(ParseScratchcardError::NonSequentialId {
+                expected: 2,
+                found: 3
+            })
+        );
     }
 
-    #[test]
-    pub fn part2_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 30);
+    aoc_core::example_tests! {
+        EXAMPLES;
+        part1_examples: part1(1);
+        part2_examples: part2(2);
     }
 
     #[test]
-    pub fn part2_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().collect::<Vec<&str>>().join("\n");
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 6227972);
+    pub fn copies_snapshot() {
+        let scratchcards = parse_scratchcards(EXAMPLE).unwrap();
+        let traces = trace::cascade(&scratchcards, trace::Overflow::Clamp).unwrap();
+        aoc_core::assert_snapshot!("copies_example", trace::render_table(&traces));
+    }
+
+    aoc_core::answer_tests! {
+        day 4;
+        part1_actual_input: part1(actual) == 26426;
+        part2_actual_input: part2(actual) == 6227972;
     }
 }
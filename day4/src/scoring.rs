@@ -0,0 +1,68 @@
+/// How many points a scratchcard is worth for a given number of matches.
+/// A card without matches is always worth nothing; `score` returns `None`
+/// when the points do not fit in a `u64`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoringRule {
+    /// 1 point for the first match, doubled for every match after it.
+    Doubling,
+    /// 1 point per match.
+    Linear,
+    /// The n-th Fibonacci number for n matches: 1, 1, 2, 3, 5, ...
+    Fibonacci,
+    /// Points looked up by number of matches, so `table[0]` is used for a
+    /// card with one match. Cards with more matches than the table covers
+    /// cannot be scored.
+    Table(Vec<u64>),
+}
+
+impl ScoringRule {
+    pub fn score(&self, matches: u32) -> Option<u64> {
+        if matches == 0 {
+            return Some(0);
+        }
+        match self {
+            Self::Doubling => 1_u64.checked_shl(matches - 1),
+            Self::Linear => Some(matches as u64),
+            Self::Fibonacci => {
+                let (mut previous, mut current): (u64, u64) = (0, 1);
+                for _ in 1..matches {
+                    (previous, current) = (current, previous.checked_add(current)?);
+                }
+                Some(current)
+            }
+            Self::Table(table) => table.get(matches as usize - 1).copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn score_rules() {
+        let rules = [
+            (ScoringRule::Doubling, [0, 1, 2, 4, 8, 16]),
+            (ScoringRule::Linear, [0, 1, 2, 3, 4, 5]),
+            (ScoringRule::Fibonacci, [0, 1, 1, 2, 3, 5]),
+            (
+                ScoringRule::Table(vec![10, 20, 30, 40, 50]),
+                [0, 10, 20, 30, 40, 50],
+            ),
+        ];
+        for (rule, expected) in rules {
+            let scores: Vec<u64> = (0..6).map(|m| rule.score(m).unwrap()).collect();
+            assert_eq!(scores, expected, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    pub fn score_beyond_limits() {
+        assert_eq!(ScoringRule::Doubling.score(40), Some(1 << 39));
+        assert_eq!(ScoringRule::Doubling.score(64), Some(1 << 63));
+        assert_eq!(ScoringRule::Doubling.score(65), None);
+        assert_eq!(ScoringRule::Fibonacci.score(93), Some(12200160415121876738));
+        assert_eq!(ScoringRule::Fibonacci.score(94), None);
+        assert_eq!(ScoringRule::Table(vec![1, 2]).score(3), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    error::Error,
+    fmt::{self, Write},
+};
+
+use aoc_core::table::{self, Align};
+
+use super::Scratchcard;
+
+#[derive(Debug, PartialEq)]
+pub struct CardTrace {
+    pub id: u32,
+    pub matches: usize,
+    pub awards: Vec<u32>,
+    pub copies: u32,
+    pub contributors: Vec<(u32, u32)>,
+}
+
+/// What to do when a card wins copies of cards past the end of the table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// Only award copies of the cards that exist.
+    Clamp,
+    /// Continue awarding from the first card, going round the table as many
+    /// times as the matches need, so a card can win copies of itself and
+    /// several copies of another card. Cards are scored once each, in table
+    /// order, so copies won of a card that has already been scored (or of
+    /// the card itself) are counted but do not win further copies.
+    Wrap,
+    /// Fail with the id of the card that ran past the end.
+    Error,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CascadeError {
+    pub card: u32,
+    pub matches: usize,
+    pub cards_after: usize,
+}
+
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "card {} wins copies of the next {} cards, but the table ends {} after it",
+            self.card, self.matches, self.cards_after
+        )
+    }
+}
+
+impl Error for CascadeError {}
+
+/// Plays out the copy cascade of part 2, recording for every card how many
+/// matches it has, which later cards it awards copies of, how many instances
+/// of it end up on the table and which earlier cards (and how many of their
+/// instances) contributed them.
+pub fn cascade(
+    scratchcards: &[Scratchcard],
+    overflow: Overflow,
+) -> Result<Vec<CardTrace>, CascadeError> {
+    let mut traces: Vec<CardTrace> = scratchcards
+        .iter()
+        .map(|s| CardTrace {
+            id: s.id(),
+            matches: s.matches().len(),
+            awards: Vec::new(),
+            copies: 1,
+            contributors: Vec::new(),
+        })
+        .collect();
+
+    for current in 0..traces.len() {
+        let (id, matches, copies) = {
+            let trace = &traces[current];
+            (trace.id, trace.matches, trace.copies)
+        };
+
+        let cards_after = traces.len() - current - 1;
+        let awarded = match overflow {
+            _ if matches <= cards_after => matches,
+            Overflow::Clamp => cards_after,
+            Overflow::Wrap => matches,
+            Overflow::Error => {
+                return Err(CascadeError {
+                    card: id,
+                    matches,
+                    cards_after,
+                })
+            }
+        };
+
+        for offset in 1..=awarded {
+            let next = (current + offset) % traces.len();
+            let next_trace = &mut traces[next];
+            next_trace.copies += copies;
+            match next_trace.contributors.last_mut() {
+                // Going round more than once awards the same card again
+                Some((contributor, contributed)) if *contributor == id => *contributed += copies,
+                _ => next_trace.contributors.push((id, copies)),
+            }
+            let next_id = next_trace.id;
+            traces[current].awards.push(next_id);
+        }
+    }
+    Ok(traces)
+}
+
+fn join(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn render_table(traces: &[CardTrace]) -> String {
+    let rows: Vec<[String; 5]> = traces
+        .iter()
+        .map(|t| {
+            let contributors: Vec<String> = t
+                .contributors
+                .iter()
+                .map(|(id, copies)| format!("{} (x{})", id, copies))
+                .collect();
+            [
+                t.id.to_string(),
+                t.matches.to_string(),
+                t.copies.to_string(),
+                if t.awards.is_empty() {
+                    "-".to_string()
+                } else {
+                    join(&t.awards)
+                },
+                if contributors.is_empty() {
+                    "-".to_string()
+                } else {
+                    contributors.join(", ")
+                },
+            ]
+        })
+        .collect();
+
+    table::render(
+        [
+            ("Card", Align::Right),
+            ("Matches", Align::Right),
+            ("Copies", Align::Right),
+            ("Awards", Align::Left),
+            ("Contributed by", Align::Left),
+        ],
+        &rows,
+    )
+}
+
+pub fn render_dot(traces: &[CardTrace]) -> String {
+    let mut dot = String::from("digraph cascade {\n    rankdir=LR;\n");
+    for t in traces {
+        writeln!(
+            dot,
+            "    card{} [label=\"Card {}\\n{} matches\\n{} copies\"];",
+            t.id, t.id, t.matches, t.copies
+        )
+        .unwrap();
+    }
+    for t in traces {
+        for (id, copies) in &t.contributors {
+            writeln!(
+                dot,
+                "    card{} -> card{} [label=\"{}\"];",
+                id, t.id, copies
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_scratchcards;
+    use super::*;
+
+    fn example_scratchcards() -> Vec<Scratchcard> {
+        parse_scratchcards(crate::EXAMPLE).unwrap()
+    }
+
+    #[test]
+    pub fn cascade_example() {
+        let traces = cascade(&example_scratchcards(), Overflow::Error).unwrap();
+        let copies: Vec<u32> = traces.iter().map(|t| t.copies).collect();
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(traces[0].awards, vec![2, 3, 4, 5]);
+        assert_eq!(traces[4].contributors, vec![(1, 1), (3, 4), (4, 8)]);
+    }
+
+    #[test]
+    pub fn render_example() {
+        let traces = cascade(&example_scratchcards(), Overflow::Error).unwrap();
+        let table = render_table(&traces);
+        println!("{}", table);
+        assert_eq!(
+            table.lines().nth(5).unwrap(),
+            "   5 |       0 |     14 | -          | 1 (x1), 3 (x4), 4 (x8)"
+        );
+
+        let dot = render_dot(&traces);
+        println!("{}", dot);
+        assert!(dot.contains("    card3 -> card5 [label=\"4\"];"));
+    }
+
+    fn overflowing_scratchcards() -> Vec<Scratchcard> {
+        parse_scratchcards(
+            "Card 1: 1 | 1
+Card 2: 1 2 3 | 1 2 3
+Card 3: 4 | 5",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    pub fn cascade_overflow_clamp() {
+        let traces = cascade(&overflowing_scratchcards(), Overflow::Clamp).unwrap();
+        let copies: Vec<u32> = traces.iter().map(|t| t.copies).collect();
+        assert_eq!(copies, vec![1, 2, 3]);
+        assert_eq!(traces[1].awards, vec![3]);
+    }
+
+    #[test]
+    pub fn cascade_overflow_wrap() {
+        let traces = cascade(&overflowing_scratchcards(), Overflow::Wrap).unwrap();
+        let copies: Vec<u32> = traces.iter().map(|t| t.copies).collect();
+        assert_eq!(copies, vec![3, 4, 3]);
+        assert_eq!(traces[1].awards, vec![3, 1, 2]);
+        assert_eq!(traces[0].contributors, vec![(2, 2)]);
+    }
+
+    #[test]
+    pub fn cascade_overflow_wrap_round_the_table() {
+        let scratchcards = parse_scratchcards("Card 1: 1 2 3 | 1 2 3\nCard 2: 4 | 5").unwrap();
+        let traces = cascade(&scratchcards, Overflow::Wrap).unwrap();
+        let copies: Vec<u32> = traces.iter().map(|t| t.copies).collect();
+        assert_eq!(copies, vec![2, 3]);
+        assert_eq!(traces[0].awards, vec![2, 1, 2]);
+        assert_eq!(traces[0].contributors, vec![(1, 1)]);
+        assert_eq!(traces[1].contributors, vec![(1, 2)]);
+        assert!(render_table(&traces).contains("| 1 (x2)"));
+    }
+
+    #[test]
+    pub fn cascade_overflow_error() {
+        let result = cascade(&overflowing_scratchcards(), Overflow::Error);
+        assert_eq!(
+            result.err(),
+            Some(CascadeError {
+                card: 2,
+                matches: 3,
+                cards_after: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day5::{get_destination_linear, Range, ResourceMap};
+
+/// Deterministic pseudo-random numbers, so every run benchmarks the same map.
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 16
+}
+
+/// `count` adjacent ranges with random lengths and destinations, given in
+/// shuffled order like a real almanac, and the end of the numbers they cover.
+fn shuffled_ranges(count: usize) -> (Vec<Range>, u64) {
+    let mut state = 2023;
+    let mut ranges: Vec<Range> = Vec::new();
+    let mut source_start = 0;
+    for _ in 0..count {
+        let length = lcg(&mut state) % 1_000_000 + 1;
+        ranges.push(Range {
+            destination_start: lcg(&mut state) % 1_000_000_000_000,
+            source_start,
+            length,
+        });
+        source_start += length;
+    }
+    for i in (1..ranges.len()).rev() {
+        let j = (lcg(&mut state) % (i as u64 + 1)) as usize;
+        ranges.swap(i, j);
+    }
+    (ranges, source_start)
+}
+
+fn bench_get_destination(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_destination");
+    for count in [10, 1_000, 5_000] {
+        let (ranges, end) = shuffled_ranges(count);
+        let map = ResourceMap::new("seed", "soil", ranges.clone());
+        let mut state = 7;
+        let sources: Vec<u64> = (0..1_000).map(|_| lcg(&mut state) % end).collect();
+
+        group.bench_with_input(BenchmarkId::new("linear", count), &sources, |b, sources| {
+            b.iter(|| {
+                for &source in sources {
+                    black_box(get_destination_linear(&ranges, black_box(source)));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("binary", count), &sources, |b, sources| {
+            b.iter(|| {
+                for &source in sources {
+                    black_box(map.get_destination(black_box(source)));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("new");
+    for count in [10, 1_000, 5_000] {
+        let (ranges, _) = shuffled_ranges(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &ranges, |b, ranges| {
+            b.iter(|| ResourceMap::new("seed", "soil", black_box(ranges.clone())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_destination, bench_new);
+criterion_main!(benches);
@@ -0,0 +1,485 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use aoc_core::parse::{labelled, numbers, parse_line, ParseError};
+
+use super::{parse_resource_map, split_sections, validate::Issue, Interval, ResourceMap};
+
+#[derive(Debug, PartialEq)]
+pub enum AlmanacError {
+    MissingSeeds,
+    DuplicateSeeds(usize),
+    StrayLine(usize),
+    UnpairedSeed(u64),
+    InvalidSeedRange(u64, u64),
+    InvalidHeader(String),
+    Syntax(ParseError),
+    DuplicateSource(String),
+    MissingLink(String),
+    CyclicLink(String),
+    Invalid(Vec<Issue>),
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSeeds => write!(f, "almanac has no seeds line"),
+            Self::DuplicateSeeds(line) => write!(f, "line {}: second seeds line", line),
+            Self::StrayLine(line) => write!(f, "line {}: numbers before any header", line),
+            Self::UnpairedSeed(seed) => write!(f, "seed {} has no number to pair with", seed),
+            Self::InvalidSeedRange(first, second) => {
+                write!(f, "seeds {} and {} do not form a range", first, second)
+            }
+            Self::InvalidHeader(header) => write!(f, "invalid map header \"{}\"", header),
+            Self::Syntax(error) => write!(f, "{}", error),
+            Self::DuplicateSource(category) => {
+                write!(f, "more than one map converts from \"{}\"", category)
+            }
+            Self::MissingLink(category) => write!(f, "no map converts from \"{}\"", category),
+            Self::CyclicLink(category) => {
+                write!(
+                    f,
+                    "maps loop back to \"{}\" before reaching the target",
+                    category
+                )
+            }
+            Self::Invalid(issues) => {
+                let issues: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+                write!(f, "invalid almanac:\n{}", issues.join("\n"))
+            }
+        }
+    }
+}
+
+impl Error for AlmanacError {}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub maps: Vec<ResourceMap>,
+    /// Suspicious but usable ranges found while parsing.
+    pub issues: Vec<Issue>,
+}
+
+impl Almanac {
+    pub fn parse(lines: &[String]) -> Result<Almanac, AlmanacError> {
+        let mut seeds: Option<Vec<u64>> = None;
+        let mut maps: Vec<ResourceMap> = Vec::new();
+        let mut issues: Vec<Issue> = Vec::new();
+        for section in split_sections(lines)? {
+            if section.header.starts_with("seeds:") {
+                if seeds.is_some() {
+                    return Err(AlmanacError::DuplicateSeeds(section.header_line));
+                }
+                let syntax =
+                    |line: usize| move |e: ParseError| AlmanacError::Syntax(e.on_line(line));
+                let mut seed_numbers: Vec<u64> =
+                    parse_line(labelled("seeds", numbers()), section.header)
+                        .map_err(syntax(section.header_line))?
+                        .flatten()
+                        .collect();
+                for (line_number, line) in &section.lines {
+                    seed_numbers.extend(
+                        parse_line(numbers::<u64>(), line)
+                            .map_err(syntax(*line_number))?
+                            .flatten(),
+                    );
+                }
+                seeds = Some(seed_numbers);
+            } else {
+                let (map, map_issues) = parse_resource_map(&section)?;
+                maps.push(map);
+                issues.extend(map_issues);
+            }
+        }
+
+        Ok(Almanac {
+            seeds: seeds.ok_or(AlmanacError::MissingSeeds)?,
+            maps,
+            issues,
+        })
+    }
+
+    /// The maps to apply, in order, to convert a `from` number into a `to`
+    /// number, found by following the categories the maps convert between.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&ResourceMap>, AlmanacError> {
+        let mut by_source: HashMap<&str, &ResourceMap> = HashMap::new();
+        for map in &self.maps {
+            if by_source.insert(&map.source, map).is_some() {
+                return Err(AlmanacError::DuplicateSource(map.source.to_string()));
+            }
+        }
+
+        let mut chain: Vec<&ResourceMap> = Vec::new();
+        let mut category = from;
+        while category != to {
+            let map = *by_source
+                .get(category)
+                .ok_or_else(|| AlmanacError::MissingLink(category.to_string()))?;
+            if chain.iter().any(|m| m.source == map.destination) || map.destination == from {
+                return Err(AlmanacError::CyclicLink(map.destination.to_string()));
+            }
+            chain.push(map);
+            category = &map.destination;
+        }
+        Ok(chain)
+    }
+
+    /// A single map converting `from` numbers straight to `to` numbers,
+    /// composed from every map along the way.
+    pub fn collapse(&self, from: &str, to: &str) -> Result<ResourceMap, AlmanacError> {
+        let chain = self.chain(from, to)?;
+        let identity = ResourceMap::new(from, from, Vec::new());
+        Ok(chain
+            .iter()
+            .fold(identity, |composed, map| composed.compose(map)))
+    }
+
+    /// The lowest `location` number of any seed in `seeds`, read off the
+    /// breakpoints of the collapsed seed-to-location map.
+    pub fn lowest_location(&self, seeds: &[Interval]) -> Result<Option<u64>, AlmanacError> {
+        let seed_to_location = self.collapse("seed", "location")?;
+        Ok(seeds
+            .iter()
+            .filter_map(|&(start, end)| seed_to_location.min_destination(start, end))
+            .min())
+    }
+
+    /// Converts `value` from the `from` category to the `to` category,
+    /// returning the number in every category along the way.
+    pub fn convert<'a>(
+        &'a self,
+        from: &'a str,
+        to: &str,
+        value: u64,
+    ) -> Result<Vec<(&'a str, u64)>, AlmanacError> {
+        let chain = self.chain(from, to)?;
+        let mut steps: Vec<(&'a str, u64)> = vec![(from, value)];
+        let mut current = value;
+        for map in chain {
+            current = map.get_destination(current);
+            steps.push((&map.destination, current));
+        }
+        Ok(steps)
+    }
+
+    /// All `from` numbers that convert to `value` in the `to` category.
+    pub fn sources(&self, from: &str, to: &str, value: u64) -> Result<Vec<u64>, AlmanacError> {
+        let chain = self.chain(from, to)?;
+        let mut current = vec![value];
+        for map in chain.iter().rev() {
+            current = current.iter().flat_map(|&v| map.get_source(v)).collect();
+            current.sort();
+            current.dedup();
+        }
+        Ok(current)
+    }
+
+    /// Finds the lowest `location` number produced by any seed in `seeds` by
+    /// trying every location from 0 upwards and walking the maps backwards.
+    pub fn lowest_location_backwards(
+        &self,
+        seeds: &[Interval],
+    ) -> Result<Option<u64>, AlmanacError> {
+        let chain = self.chain("seed", "location")?;
+
+        // Every location is either the destination of a range or a number
+        // passed through unchanged, so none lie beyond the largest of these.
+        let highest = chain
+            .iter()
+            .flat_map(|m| m.ranges().iter().map(|r| r.destination_start + r.length))
+            .chain(seeds.iter().map(|&(_, end)| end))
+            .max()
+            .unwrap_or(0);
+
+        for location in 0..highest {
+            let mut current = vec![location];
+            for map in chain.iter().rev() {
+                current = current.iter().flat_map(|&v| map.get_source(v)).collect();
+            }
+            let found = current.iter().any(|seed| {
+                seeds
+                    .iter()
+                    .any(|&(start, end)| *seed >= start && *seed < end)
+            });
+            if found {
+                return Ok(Some(location));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Converts the half-open interval `[start, end)` from the `from` category
+    /// to the `to` category, returning the intervals in every category along
+    /// the way.
+    pub fn convert_range<'a>(
+        &'a self,
+        from: &'a str,
+        to: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<(&'a str, Vec<Interval>)>, AlmanacError> {
+        let chain = self.chain(from, to)?;
+        let mut steps: Vec<(&'a str, Vec<Interval>)> = vec![(from, vec![(start, end)])];
+        let mut current = vec![(start, end)];
+        for map in chain {
+            current = current
+                .iter()
+                .flat_map(|&(start, end)| map.get_destination_ranges(start, end))
+                .collect();
+            current.sort();
+            steps.push((&map.destination, current.clone()));
+        }
+        Ok(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::validate::IssueKind;
+    use super::*;
+
+    fn almanac(input: &str) -> Almanac {
+        let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+        Almanac::parse(&lines).unwrap()
+    }
+
+    fn categories(chain: &[&ResourceMap]) -> Vec<String> {
+        chain
+            .iter()
+            .map(|m| format!("{}-to-{}", m.source, m.destination))
+            .collect()
+    }
+
+    #[test]
+    pub fn chain_follows_categories_in_any_order() {
+        let almanac = almanac(
+            "seeds: 1 2
+
+soil-to-water map:
+10 0 5
+
+seed-to-soil map:
+0 1 5
+
+water-to-location map:
+1 1 1
+",
+        );
+        assert_eq!(almanac.seeds, vec![1, 2]);
+        let chain = almanac.chain("seed", "location").unwrap();
+        assert_eq!(
+            categories(&chain),
+            vec!["seed-to-soil", "soil-to-water", "water-to-location"]
+        );
+        assert_eq!(
+            categories(&almanac.chain("soil", "water").unwrap()),
+            vec!["soil-to-water"]
+        );
+        assert!(almanac.chain("soil", "soil").unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn chain_reports_missing_link() {
+        let almanac = almanac("seeds: 1\n\nseed-to-soil map:\n0 1 5\n\n");
+        assert_eq!(
+            almanac.chain("seed", "location").err(),
+            Some(AlmanacError::MissingLink("soil".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn chain_reports_cyclic_link() {
+        let almanac =
+            almanac("seeds: 1\n\nseed-to-soil map:\n0 1 5\n\nsoil-to-seed map:\n0 1 5\n\n");
+        assert_eq!(
+            almanac.chain("seed", "location").err(),
+            Some(AlmanacError::CyclicLink("seed".to_string()))
+        );
+    }
+
+    fn example_almanac() -> Almanac {
+        almanac(crate::EXAMPLE)
+    }
+
+    #[test]
+    pub fn convert_through_subchain() {
+        let almanac = example_almanac();
+        let steps = almanac.convert("seed", "humidity", 79).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                ("seed", 79),
+                ("soil", 81),
+                ("fertilizer", 81),
+                ("water", 81),
+                ("light", 74),
+                ("temperature", 78),
+                ("humidity", 78)
+            ]
+        );
+        assert_eq!(
+            almanac.convert("water", "light", 81).unwrap().last(),
+            Some(&("light", 74))
+        );
+    }
+
+    #[test]
+    pub fn convert_range_splits_at_boundaries() {
+        let almanac = example_almanac();
+        let steps = almanac.convert_range("seed", "soil", 95, 100).unwrap();
+        assert_eq!(steps[1], ("soil", vec![(50, 52), (97, 100)]));
+
+        let steps = almanac.convert_range("seed", "location", 82, 83).unwrap();
+        assert_eq!(steps.last(), Some(&("location", vec![(46, 47)])));
+    }
+
+    #[test]
+    pub fn sources_invert_convert() {
+        let almanac = example_almanac();
+        assert_eq!(almanac.sources("seed", "location", 82).unwrap(), vec![79]);
+        assert_eq!(almanac.sources("seed", "location", 46).unwrap(), vec![82]);
+        for seed in [79, 14, 55, 13] {
+            let location = almanac.convert("seed", "location", seed).unwrap()[7].1;
+            assert!(almanac
+                .sources("seed", "location", location)
+                .unwrap()
+                .contains(&seed));
+        }
+    }
+
+    #[test]
+    pub fn get_source_includes_passthrough() {
+        let almanac = example_almanac();
+        let seed_to_soil = &almanac.maps[0];
+        // 50 is the destination of seed 98, but seed 50 itself maps to 52
+        assert_eq!(seed_to_soil.get_source(50), vec![98]);
+        // 52 is the destination of seed 50 and 52 itself is remapped too
+        assert_eq!(seed_to_soil.get_source(52), vec![50]);
+        // 10 is not covered by any range
+        assert_eq!(seed_to_soil.get_source(10), vec![10]);
+        // 99 is the destination of seed 97, while seed 99 itself maps to 51
+        // instead of passing through, so only 97 maps to it
+        assert_eq!(seed_to_soil.get_source(99), vec![97]);
+    }
+
+    #[test]
+    pub fn lowest_location_backwards_example() {
+        let almanac = example_almanac();
+        let lowest = almanac.lowest_location_backwards(&[(79, 93), (55, 68)]);
+        assert_eq!(lowest, Ok(Some(46)));
+        let lowest = almanac.lowest_location_backwards(&[(79, 80), (14, 15), (55, 56), (13, 14)]);
+        assert_eq!(lowest, Ok(Some(35)));
+    }
+
+    #[test]
+    pub fn collapse_example() {
+        let almanac = example_almanac();
+        let seed_to_location = almanac.collapse("seed", "location").unwrap();
+        assert_eq!(seed_to_location.source, "seed");
+        assert_eq!(seed_to_location.destination, "location");
+        for seed in 0..120 {
+            let steps = almanac.convert("seed", "location", seed).unwrap();
+            assert_eq!(seed_to_location.get_destination(seed), steps[7].1);
+        }
+        assert_eq!(almanac.lowest_location(&[(79, 93), (55, 68)]), Ok(Some(46)));
+    }
+
+    #[test]
+    pub fn parse_reports_range_issues() {
+        let almanac = almanac(
+            "seeds: 1
+
+seed-to-soil map:
+0 10 5
+0 12 5
+0 30 0
+0 20 5
+
+soil-to-location map:
+0 0 1
+",
+        );
+        assert_eq!(
+            almanac.issues,
+            vec![
+                Issue {
+                    line: 5,
+                    kind: IssueKind::Overlap(4)
+                },
+                Issue {
+                    line: 6,
+                    kind: IssueKind::ZeroLength
+                },
+                Issue {
+                    line: 7,
+                    kind: IssueKind::Gap((17, 20))
+                },
+            ]
+        );
+        assert_eq!(
+            almanac.issues[0].to_string(),
+            "line 5: range overlaps the range on line 4"
+        );
+        assert!(example_almanac()
+            .issues
+            .iter()
+            .all(|i| matches!(i.kind, IssueKind::Gap(_))));
+    }
+
+    #[test]
+    pub fn parse_rejects_overflowing_range() {
+        let lines: Vec<String> = [
+            "seeds: 1",
+            "",
+            "seed-to-soil map:",
+            "0 1 5",
+            "18446744073709551615 0 2",
+            "",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        assert_eq!(
+            Almanac::parse(&lines).err(),
+            Some(AlmanacError::Invalid(vec![Issue {
+                line: 5,
+                kind: IssueKind::Overflow
+            }]))
+        );
+    }
+
+    #[test]
+    pub fn parse_reports_invalid_header() {
+        let lines: Vec<String> = ["seeds: 1", "", "seed to soil map:", "0 1 5", ""]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(
+            Almanac::parse(&lines).err(),
+            Some(AlmanacError::InvalidHeader("seed to soil map:".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn parse_reports_where_a_line_is_malformed() {
+        let lines: Vec<String> = ["seeds: 1 2", "3 x", "", "a-to-b map:", "0 1"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let error = Almanac::parse(&lines).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected end of line, found \"x\""
+        );
+
+        let lines: Vec<String> = ["seeds: 1", "a-to-b map:", "", "0 1"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let error = Almanac::parse(&lines).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 4: expected number, found end of line"
+        );
+    }
+}
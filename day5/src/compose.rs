@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+
+use super::{Range, ResourceMap};
+
+impl ResourceMap {
+    /// Splits `[start, end)` into consecutive pieces that are each shifted by
+    /// a single offset, so every source number in a piece maps to
+    /// `destination_start + (source - source_start)`. Numbers not covered by
+    /// any range form pieces that map to themselves.
+    pub fn segments(&self, start: u64, end: u64) -> Vec<Range> {
+        let mut breakpoints: BTreeSet<u64> = BTreeSet::from([start, end]);
+        for range in &self.ranges {
+            let source_end = range.source_start.saturating_add(range.length);
+            for breakpoint in [range.source_start, source_end] {
+                if breakpoint > start && breakpoint < end {
+                    breakpoints.insert(breakpoint);
+                }
+            }
+        }
+
+        let breakpoints: Vec<u64> = breakpoints.into_iter().collect();
+        let mut segments: Vec<Range> = Vec::new();
+        for window in breakpoints.windows(2) {
+            let (source_start, source_end) = (window[0], window[1]);
+            let destination_start = self.get_destination(source_start);
+            match segments.last_mut() {
+                // A shadowed breakpoint doesn't change the offset, keep going
+                Some(last) if last.destination_start + last.length == destination_start => {
+                    last.length += source_end - source_start;
+                }
+                _ => segments.push(Range {
+                    destination_start,
+                    source_start,
+                    length: source_end - source_start,
+                }),
+            }
+        }
+        segments
+    }
+
+    /// A single map equivalent to applying `self` and then `next`.
+    pub fn compose(&self, next: &ResourceMap) -> ResourceMap {
+        let mut ranges: Vec<Range> = Vec::new();
+        for segment in self.segments(0, u64::MAX) {
+            let destination_end = segment.destination_start.saturating_add(segment.length);
+            for piece in next.segments(segment.destination_start, destination_end) {
+                let source_start =
+                    segment.source_start + (piece.source_start - segment.destination_start);
+                if piece.destination_start == source_start {
+                    continue;
+                }
+                match ranges.last_mut() {
+                    // Merge with the previous range if it continues it
+                    Some(last)
+                        if last.source_start + last.length == source_start
+                            && last.destination_start + last.length == piece.destination_start =>
+                    {
+                        last.length += piece.length;
+                    }
+                    _ => ranges.push(Range {
+                        destination_start: piece.destination_start,
+                        source_start,
+                        length: piece.length,
+                    }),
+                }
+            }
+        }
+        ResourceMap::new(&self.source, &next.destination, ranges)
+    }
+
+    /// The lowest destination of any source number in `[start, end)`. Within a
+    /// segment destinations only grow, so only segment starts need checking.
+    pub fn min_destination(&self, start: u64, end: u64) -> Option<u64> {
+        self.segments(start, end)
+            .iter()
+            .map(|s| s.destination_start)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource_map(source: &str, destination: &str, ranges: &[(u64, u64, u64)]) -> ResourceMap {
+        ResourceMap::new(
+            source,
+            destination,
+            ranges
+                .iter()
+                .map(|&(destination_start, source_start, length)| Range {
+                    destination_start,
+                    source_start,
+                    length,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn segments_cover_gaps_and_respect_shadowing() {
+        // The second range is partly shadowed by the first
+        let map = resource_map("a", "b", &[(100, 10, 5), (200, 12, 10)]);
+        let segments: Vec<(u64, u64, u64)> = map
+            .segments(0, 30)
+            .iter()
+            .map(|s| (s.destination_start, s.source_start, s.length))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![(0, 0, 10), (100, 10, 5), (203, 15, 7), (22, 22, 8)]
+        );
+    }
+
+    #[test]
+    pub fn compose_matches_applying_in_turn() {
+        let first = resource_map("seed", "soil", &[(50, 98, 2), (52, 50, 48)]);
+        let second = resource_map(
+            "soil",
+            "fertilizer",
+            &[(0, 15, 37), (37, 52, 2), (39, 0, 15)],
+        );
+        let composed = first.compose(&second);
+        assert_eq!(composed.source, "seed");
+        assert_eq!(composed.destination, "fertilizer");
+        for seed in 0..200 {
+            assert_eq!(
+                composed.get_destination(seed),
+                second.get_destination(first.get_destination(seed)),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    pub fn min_destination_over_interval() {
+        let map = resource_map("a", "b", &[(100, 10, 5), (3, 20, 5)]);
+        assert_eq!(map.min_destination(12, 30), Some(3));
+        assert_eq!(map.min_destination(10, 15), Some(100));
+        assert_eq!(map.min_destination(8, 15), Some(8));
+        assert_eq!(map.min_destination(5, 5), None);
+    }
+}
@@ -0,0 +1,108 @@
+use super::{almanac::AlmanacError, Interval};
+
+/// How to read the numbers on the seeds line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedSpec {
+    /// Every number is a seed on its own, as in part 1.
+    Individual,
+    /// Pairs of range start and length, as in part 2.
+    StartLength,
+    /// Pairs of range start and inclusive range end.
+    StartEnd,
+    /// Ignore the seeds line and use these ranges instead.
+    Ranges(Vec<Interval>),
+}
+
+fn pairs(seeds: &[u64]) -> Result<Vec<(u64, u64)>, AlmanacError> {
+    let chunks = seeds.chunks_exact(2);
+    if let [seed] = chunks.remainder() {
+        return Err(AlmanacError::UnpairedSeed(*seed));
+    }
+    Ok(chunks.map(|pair| (pair[0], pair[1])).collect())
+}
+
+impl SeedSpec {
+    /// The seeds described by the numbers on the seeds line, as intervals.
+    pub fn intervals(&self, seeds: &[u64]) -> Result<Vec<Interval>, AlmanacError> {
+        let invalid = |start: u64, end: u64| AlmanacError::InvalidSeedRange(start, end);
+        match self {
+            Self::Individual => seeds
+                .iter()
+                .map(|&seed| Ok((seed, seed.checked_add(1).ok_or(invalid(seed, seed))?)))
+                .collect(),
+            Self::StartLength => pairs(seeds)?
+                .into_iter()
+                .map(|(start, length)| {
+                    let end = start.checked_add(length).ok_or(invalid(start, length))?;
+                    Ok((start, end))
+                })
+                .collect(),
+            Self::StartEnd => pairs(seeds)?
+                .into_iter()
+                .map(|(start, end)| match end.checked_add(1) {
+                    Some(end_exclusive) if start <= end => Ok((start, end_exclusive)),
+                    _ => Err(invalid(start, end)),
+                })
+                .collect(),
+            Self::Ranges(ranges) => ranges
+                .iter()
+                .map(|&(start, end)| {
+                    if start > end {
+                        return Err(invalid(start, end));
+                    }
+                    Ok((start, end))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn intervals_for_each_spec() {
+        let seeds = [79, 14, 55, 13];
+        assert_eq!(
+            SeedSpec::Individual.intervals(&seeds),
+            Ok(vec![(79, 80), (14, 15), (55, 56), (13, 14)])
+        );
+        assert_eq!(
+            SeedSpec::StartLength.intervals(&seeds),
+            Ok(vec![(79, 93), (55, 68)])
+        );
+        assert_eq!(
+            SeedSpec::StartEnd.intervals(&[10, 14, 55, 55]),
+            Ok(vec![(10, 15), (55, 56)])
+        );
+        assert_eq!(
+            SeedSpec::Ranges(vec![(1, 2)]).intervals(&seeds),
+            Ok(vec![(1, 2)])
+        );
+    }
+
+    #[test]
+    pub fn intervals_reject_bad_seeds() {
+        assert_eq!(
+            SeedSpec::StartLength.intervals(&[79, 14, 55]),
+            Err(AlmanacError::UnpairedSeed(55))
+        );
+        assert_eq!(
+            SeedSpec::StartEnd.intervals(&[79, 14]),
+            Err(AlmanacError::InvalidSeedRange(79, 14))
+        );
+    }
+
+    #[test]
+    pub fn intervals_reject_backwards_ranges() {
+        assert_eq!(
+            SeedSpec::Ranges(vec![(1, 2), (90, 80)]).intervals(&[]),
+            Err(AlmanacError::InvalidSeedRange(90, 80))
+        );
+        assert_eq!(
+            SeedSpec::Ranges(vec![(5, 5)]).intervals(&[]),
+            Ok(vec![(5, 5)])
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use super::{Interval, Range};
+
+#[derive(Debug, PartialEq)]
+pub enum IssueKind {
+    /// Overlaps the range on the given line, which shadows it or is shadowed
+    /// by it depending on which comes first in the file.
+    Overlap(usize),
+    ZeroLength,
+    /// The source or destination end does not fit in a `u64`.
+    Overflow,
+    /// Numbers between the previous range and this one map to themselves.
+    Gap(Interval),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Issue {
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl Issue {
+    /// Whether the map cannot be used at all, as opposed to merely being
+    /// suspicious.
+    pub fn is_error(&self) -> bool {
+        self.kind == IssueKind::Overflow
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            IssueKind::Overlap(line) => write!(f, "range overlaps the range on line {}", line),
+            IssueKind::ZeroLength => write!(f, "range has length 0"),
+            IssueKind::Overflow => write!(f, "range ends past {}", u64::MAX),
+            IssueKind::Gap((start, end)) => {
+                write!(f, "gap between ranges from {} to {}", start, end)
+            }
+        }
+    }
+}
+
+/// Checks the ranges of one map, where `lines[i]` is the line `ranges[i]`
+/// was read from.
+pub(crate) fn validate_ranges(ranges: &[Range], lines: &[usize]) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut sorted: Vec<(u64, u64, usize)> = Vec::new();
+    for (range, &line) in ranges.iter().zip(lines) {
+        let source_end = range.source_start.checked_add(range.length);
+        let destination_end = range.destination_start.checked_add(range.length);
+        match (source_end, destination_end) {
+            (Some(_), Some(_)) if range.length == 0 => issues.push(Issue {
+                line,
+                kind: IssueKind::ZeroLength,
+            }),
+            (Some(source_end), Some(_)) => sorted.push((range.source_start, source_end, line)),
+            _ => issues.push(Issue {
+                line,
+                kind: IssueKind::Overflow,
+            }),
+        }
+    }
+    sorted.sort();
+
+    // The furthest any range seen so far reaches, and the line of that range
+    let mut reach: Option<(u64, usize)> = None;
+    for (start, end, line) in sorted {
+        if let Some((reach_end, reach_line)) = reach {
+            if start < reach_end {
+                issues.push(Issue {
+                    line: line.max(reach_line),
+                    kind: IssueKind::Overlap(line.min(reach_line)),
+                });
+            } else if start > reach_end {
+                issues.push(Issue {
+                    line,
+                    kind: IssueKind::Gap((reach_end, start)),
+                });
+            }
+            if end <= reach_end {
+                continue;
+            }
+        }
+        reach = Some((end, line));
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
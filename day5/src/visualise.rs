@@ -0,0 +1,230 @@
+use std::fmt::Write;
+
+use super::{
+    almanac::{Almanac, AlmanacError},
+    Interval, ResourceMap,
+};
+
+/// An interval of numbers in one category and the intervals it fragments
+/// into in the next category.
+#[derive(Debug, PartialEq)]
+pub struct Fragment {
+    pub category: String,
+    pub interval: Interval,
+    pub children: Vec<Fragment>,
+}
+
+impl Fragment {
+    fn leaves(&self) -> usize {
+        if self.children.is_empty() {
+            1
+        } else {
+            self.children.iter().map(|c| c.leaves()).sum()
+        }
+    }
+
+    fn lowest(&self) -> u64 {
+        if self.children.is_empty() {
+            self.interval.0
+        } else {
+            self.children.iter().map(|c| c.lowest()).min().unwrap()
+        }
+    }
+}
+
+fn fragment(category: &str, interval: Interval, chain: &[&ResourceMap]) -> Fragment {
+    let children = match chain.split_first() {
+        Some((map, rest)) => map
+            .get_destination_ranges(interval.0, interval.1)
+            .into_iter()
+            .map(|i| fragment(&map.destination, i, rest))
+            .collect(),
+        None => Vec::new(),
+    };
+    Fragment {
+        category: category.to_string(),
+        interval,
+        children,
+    }
+}
+
+/// Follows every seed interval from `seed` to `location`, splitting it
+/// wherever a map sends parts of it to different places.
+pub fn fragments(almanac: &Almanac, seeds: &[Interval]) -> Result<Vec<Fragment>, AlmanacError> {
+    let chain = almanac.chain("seed", "location")?;
+    Ok(seeds
+        .iter()
+        .map(|&interval| fragment("seed", interval, &chain))
+        .collect())
+}
+
+fn lowest(fragments: &[Fragment]) -> Option<u64> {
+    fragments.iter().map(|f| f.lowest()).min()
+}
+
+fn render_tree_node(
+    tree: &mut String,
+    fragment: &Fragment,
+    prefix: &str,
+    branch: (&str, &str),
+    lowest: &mut Option<u64>,
+) {
+    let (branch, continuation) = branch;
+    write!(
+        tree,
+        "{}{}{} {}..{}",
+        prefix, branch, fragment.category, fragment.interval.0, fragment.interval.1
+    )
+    .unwrap();
+    // Only point out the first interval holding the lowest location
+    if fragment.children.is_empty() && *lowest == Some(fragment.interval.0) {
+        tree.push_str("  <- lowest location");
+        *lowest = None;
+    }
+    tree.push('\n');
+
+    let prefix = format!("{}{}", prefix, continuation);
+    for (index, child) in fragment.children.iter().enumerate() {
+        let branch = if index + 1 == fragment.children.len() {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        render_tree_node(tree, child, &prefix, branch, lowest);
+    }
+}
+
+/// Renders the fragments as an indented tree, pointing out the location
+/// interval holding the lowest location.
+pub fn render_tree(fragments: &[Fragment]) -> String {
+    let mut tree = String::new();
+    let mut lowest = lowest(fragments);
+    for fragment in fragments {
+        render_tree_node(&mut tree, fragment, "", ("", ""), &mut lowest);
+    }
+    tree
+}
+
+const COLUMN_WIDTH: usize = 170;
+const ROW_HEIGHT: usize = 24;
+
+fn render_svg_node(
+    svg: &mut String,
+    fragment: &Fragment,
+    (column, row): (usize, usize),
+    lowest: &mut Option<u64>,
+) {
+    let highlighted = *lowest == Some(fragment.lowest());
+    let (x, y) = (column * COLUMN_WIDTH, (row + 1) * ROW_HEIGHT);
+    let height = fragment.leaves() * ROW_HEIGHT;
+    writeln!(
+        svg,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#555\"/>",
+        x + 2,
+        y + 2,
+        COLUMN_WIDTH - 4,
+        height - 4,
+        if highlighted { "#f9d342" } else { "#dde4f0" }
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "  <text x=\"{}\" y=\"{}\">{}..{}</text>",
+        x + 8,
+        y + 17,
+        fragment.interval.0,
+        fragment.interval.1
+    )
+    .unwrap();
+
+    let mut row = row;
+    for child in &fragment.children {
+        render_svg_node(svg, child, (column + 1, row), lowest);
+        row += child.leaves();
+    }
+    // Only highlight the first path to the lowest location
+    if highlighted && fragment.children.is_empty() {
+        *lowest = None;
+    }
+}
+
+/// Renders the fragments as an SVG timeline, one column per category from
+/// seed to location, highlighting the path to the lowest location.
+pub fn render_svg(fragments: &[Fragment]) -> String {
+    let mut categories: Vec<&str> = Vec::new();
+    let mut current = fragments.first();
+    while let Some(fragment) = current {
+        categories.push(&fragment.category);
+        current = fragment.children.first();
+    }
+    let rows: usize = fragments.iter().map(|f| f.leaves()).sum();
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"12\">",
+        categories.len() * COLUMN_WIDTH,
+        (rows + 1) * ROW_HEIGHT
+    )
+    .unwrap();
+    for (column, category) in categories.iter().enumerate() {
+        writeln!(
+            svg,
+            "  <text x=\"{}\" y=\"16\" font-weight=\"bold\">{}</text>",
+            column * COLUMN_WIDTH + 8,
+            category
+        )
+        .unwrap();
+    }
+
+    let mut lowest = lowest(fragments);
+    let mut row = 0;
+    for fragment in fragments {
+        render_svg_node(&mut svg, fragment, (0, row), &mut lowest);
+        row += fragment.leaves();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_fragments() -> Vec<Fragment> {
+        let lines: Vec<String> = crate::EXAMPLE.lines().map(|l| l.to_string()).collect();
+        let almanac = Almanac::parse(&lines).unwrap();
+        fragments(&almanac, &[(79, 93), (55, 68)]).unwrap()
+    }
+
+    #[test]
+    pub fn fragments_example() {
+        let fragments = example_fragments();
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0].children[0].category, "soil");
+        assert_eq!(fragments[0].children[0].interval, (81, 95));
+        assert_eq!(lowest(&fragments), Some(46));
+    }
+
+    #[test]
+    pub fn render_tree_example() {
+        let tree = render_tree(&example_fragments());
+        println!("{}", tree);
+        assert!(tree.starts_with("seed 79..93\n└── soil 81..95\n    └── fertilizer 81..95\n"));
+        assert_eq!(tree.matches("<- lowest location").count(), 1);
+        assert!(tree.contains("location 46..56  <- lowest location"));
+    }
+
+    #[test]
+    pub fn render_svg_example() {
+        let fragments = example_fragments();
+        let svg = render_svg(&fragments);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // One box per fragment plus a heading per category
+        assert_eq!(svg.matches("<rect").count(), 27);
+        assert_eq!(svg.matches("<text").count(), 27 + 8);
+        assert_eq!(svg.matches("#f9d342").count(), 8);
+    }
+}
@@ -0,0 +1,333 @@
+use super::winning_holds;
+
+/// How far a boat travels for a given hold time. Holding the button longer
+/// builds up more speed but leaves less time to travel, so the default search
+/// expects a model to travel further the longer the button is held up to some
+/// hold time, and less from then on. Models where that does not hold override
+/// `winning_holds`.
+pub trait BoatModel {
+    /// Distance travelled in a race of `time` ms when holding the button for
+    /// the first `hold` ms.
+    fn distance(&self, hold: u64, time: u64) -> f64;
+
+    /// Whether holding the button for `hold` ms beats `record`. Models with
+    /// integer distances override this to compare exactly.
+    fn beats(&self, hold: u64, time: u64, record: u64) -> bool {
+        self.distance(hold, time) > record as f64
+    }
+
+    /// Shortest and longest hold times that beat `record`.
+    fn winning_holds(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        default_winning_holds(self, time, record)
+    }
+
+    fn ways_to_win(&self, time: u64, record: u64) -> u64 {
+        match self.winning_holds(time, record) {
+            Some((min, max)) => max - min + 1,
+            None => 0,
+        }
+    }
+}
+
+/// Winning holds found by searching for the furthest travelling hold and then
+/// for where each side of it stops beating the record.
+fn default_winning_holds<M: BoatModel + ?Sized>(
+    model: &M,
+    time: u64,
+    record: u64,
+) -> Option<(u64, u64)> {
+    let peak = peak_hold(model, time);
+    if !model.beats(peak, time, record) {
+        return None;
+    }
+    let first = partition_point(0, peak, |hold| !model.beats(hold, time, record));
+    Some((first, last_winning_hold(model, peak, time, record)))
+}
+
+/// First value in `start..end` for which `pred` no longer holds, given that it
+/// holds for every value before that and none after.
+fn partition_point(start: u64, end: u64, pred: impl Fn(u64) -> bool) -> u64 {
+    let (mut low, mut high) = (start, end);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if pred(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+/// Longest hold from `first` on that beats `record`, given that `first` does
+/// and that the holds that do are not interrupted by ones that do not.
+fn last_winning_hold<M: BoatModel + ?Sized>(model: &M, first: u64, time: u64, record: u64) -> u64 {
+    // Searching up to `time` rather than past it keeps `time == u64::MAX` in range
+    match partition_point(first, time, |hold| model.beats(hold, time, record)) {
+        end if end == time && model.beats(time, time, record) => time,
+        end => end - 1,
+    }
+}
+
+/// Hold time travelling the furthest, by ternary search.
+fn peak_hold<M: BoatModel + ?Sized>(model: &M, time: u64) -> u64 {
+    let (mut low, mut high) = (0, time);
+    while high - low > 2 {
+        let first_third = low + (high - low) / 3;
+        let second_third = high - (high - low) / 3;
+        if model.distance(first_third, time) < model.distance(second_third, time) {
+            low = first_third + 1;
+        } else {
+            high = second_third;
+        }
+    }
+    (low..=high)
+        .max_by(|a, b| {
+            model
+                .distance(*a, time)
+                .total_cmp(&model.distance(*b, time))
+        })
+        .unwrap()
+}
+
+/// Every millisecond held adds 1 mm/ms of speed, as in the puzzle.
+pub struct Linear;
+
+impl BoatModel for Linear {
+    fn distance(&self, hold: u64, time: u64) -> f64 {
+        (hold as u128 * (time - hold) as u128) as f64
+    }
+
+    fn beats(&self, hold: u64, time: u64, record: u64) -> bool {
+        hold as u128 * (time - hold) as u128 > record as u128
+    }
+
+    fn winning_holds(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        winning_holds(time, record)
+    }
+}
+
+/// Every millisecond held adds `rate` mm/ms of speed.
+pub struct ChargeRate(pub u64);
+
+impl BoatModel for ChargeRate {
+    fn distance(&self, hold: u64, time: u64) -> f64 {
+        self.0 as f64 * Linear.distance(hold, time)
+    }
+
+    fn beats(&self, hold: u64, time: u64, record: u64) -> bool {
+        self.0 as u128 * hold as u128 * (time - hold) as u128 > record as u128
+    }
+
+    fn winning_holds(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        // rate * d > record exactly when d > record / rate, rounded down
+        match self.0 {
+            0 => None,
+            rate => winning_holds(time, record / rate),
+        }
+    }
+}
+
+/// The boat keeps speeding up by `acceleration` mm/ms every millisecond after
+/// the button is released.
+pub struct Accelerating {
+    pub acceleration: u64,
+}
+
+impl Accelerating {
+    fn exact_distance(&self, hold: u64, time: u64) -> u128 {
+        let travel_time = (time - hold) as u128;
+        let gained = travel_time * travel_time.saturating_sub(1) / 2;
+        hold as u128 * travel_time + self.acceleration as u128 * gained
+    }
+}
+
+impl BoatModel for Accelerating {
+    fn distance(&self, hold: u64, time: u64) -> f64 {
+        self.exact_distance(hold, time) as f64
+    }
+
+    fn beats(&self, hold: u64, time: u64, record: u64) -> bool {
+        self.exact_distance(hold, time) > record as u128
+    }
+
+    fn winning_holds(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        if self.acceleration < 2 {
+            return default_winning_holds(self, time, record);
+        }
+        // Releasing the button a millisecond earlier starts 1 mm/ms slower but
+        // adds a millisecond of travel, over which the boat gains at least
+        // 2 mm/ms, so the distance never grows with the hold time and the
+        // winning holds, if any, start at 0.
+        if !self.beats(0, time, record) {
+            return None;
+        }
+        Some((0, last_winning_hold(self, 0, time, record)))
+    }
+}
+
+/// Every millisecond held adds `rate` mm/ms of speed, up to `top_speed`.
+pub struct Capped {
+    pub rate: u64,
+    pub top_speed: u64,
+}
+
+impl Capped {
+    fn exact_distance(&self, hold: u64, time: u64) -> u128 {
+        let speed = (self.rate as u128 * hold as u128).min(self.top_speed as u128);
+        speed * (time - hold) as u128
+    }
+}
+
+impl BoatModel for Capped {
+    fn distance(&self, hold: u64, time: u64) -> f64 {
+        self.exact_distance(hold, time) as f64
+    }
+
+    fn beats(&self, hold: u64, time: u64, record: u64) -> bool {
+        self.exact_distance(hold, time) > record as u128
+    }
+}
+
+/// Every millisecond held adds `rate` mm/ms of speed, which then decays by a
+/// factor of e^-`drag` every millisecond after the button is released.
+pub struct Drag {
+    pub rate: f64,
+    pub drag: f64,
+}
+
+impl BoatModel for Drag {
+    fn distance(&self, hold: u64, time: u64) -> f64 {
+        let speed = self.rate * hold as f64;
+        let travel_time = (time - hold) as f64;
+        if self.drag == 0.0 {
+            speed * travel_time
+        } else {
+            speed * -(-self.drag * travel_time).exp_m1() / self.drag
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(model: &dyn BoatModel, time: u64, record: u64) -> Option<(u64, u64)> {
+        let winning: Vec<u64> = (0..=time)
+            .filter(|&hold| model.beats(hold, time, record))
+            .collect();
+        Some((*winning.first()?, *winning.last()?))
+    }
+
+    fn assert_matches_brute_force(model: &dyn BoatModel) {
+        for time in 0..40 {
+            for record in (0..400).step_by(7) {
+                assert_eq!(
+                    model.winning_holds(time, record),
+                    brute_force(model, time, record),
+                    "time {} record {}",
+                    time,
+                    record
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn linear_example() {
+        assert_eq!(Linear.ways_to_win(7, 9), 4);
+        assert_eq!(Linear.ways_to_win(15, 40), 8);
+        assert_eq!(Linear.ways_to_win(30, 200), 9);
+        assert_eq!(Linear.ways_to_win(71530, 940200), 71503);
+    }
+
+    #[test]
+    pub fn models_match_brute_force() {
+        assert_matches_brute_force(&Linear);
+        assert_matches_brute_force(&ChargeRate(0));
+        assert_matches_brute_force(&ChargeRate(3));
+        for acceleration in 0..4 {
+            assert_matches_brute_force(&Accelerating { acceleration });
+        }
+        assert_matches_brute_force(&Capped {
+            rate: 2,
+            top_speed: 15,
+        });
+        assert_matches_brute_force(&Drag {
+            rate: 1.5,
+            drag: 0.1,
+        });
+    }
+
+    #[test]
+    pub fn accelerating_matches_brute_force() {
+        // Includes races shorter than the acceleration, and accelerations
+        // where the hold that travels furthest is not in the middle
+        for acceleration in 0..40 {
+            let model = Accelerating { acceleration };
+            for time in 0..30 {
+                for record in (0..2000).step_by(13) {
+                    assert_eq!(
+                        model.winning_holds(time, record),
+                        brute_force(&model, time, record),
+                        "acceleration {} time {} record {}",
+                        acceleration,
+                        time,
+                        record
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn longest_race() {
+        let time = u64::MAX;
+        let capped = Capped {
+            rate: 1,
+            top_speed: 1,
+        };
+        assert_eq!(capped.winning_holds(time, 0), Some((1, time - 1)));
+        assert_eq!(default_winning_holds(&Linear, time, 0), Some((1, time - 1)));
+    }
+
+    #[test]
+    pub fn models_reduce_to_linear() {
+        for (time, record) in [(7, 9), (15, 40), (30, 200), (71530, 940200)] {
+            let expected = Linear.winning_holds(time, record);
+            assert_eq!(default_winning_holds(&Linear, time, record), expected);
+            assert_eq!(ChargeRate(1).winning_holds(time, record), expected);
+            assert_eq!(
+                Accelerating { acceleration: 0 }.winning_holds(time, record),
+                expected
+            );
+            assert_eq!(
+                Capped {
+                    rate: 1,
+                    top_speed: u64::MAX
+                }
+                .winning_holds(time, record),
+                expected
+            );
+            assert_eq!(
+                Drag {
+                    rate: 1.0,
+                    drag: 0.0
+                }
+                .winning_holds(time, record),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    pub fn capped_speed_narrows_the_winning_holds() {
+        // Capped at 4 mm/ms, holding longer than 4 ms only wastes time
+        let capped = Capped {
+            rate: 1,
+            top_speed: 4,
+        };
+        assert_eq!(capped.winning_holds(10, 21), Some((4, 4)));
+        assert_eq!(capped.winning_holds(10, 24), None);
+    }
+}
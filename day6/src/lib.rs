@@ -1,110 +1,337 @@
-fn get_digits(str: &str) -> Vec<u64> {
-    str.split_whitespace()
-        .map(|n| n.trim().parse::<u64>().unwrap())
-        .collect()
+use std::{error::Error, fmt, str::FromStr};
+
+use aoc_core::numbers::Digits;
+use aoc_core::parse::{alt, digit_groups, labelled, map, parse_lines, PResult, ParseError};
+use aoc_core::puzzle::Example;
+use num_bigint::BigUint;
+
+pub mod boat;
+pub mod outcome;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseRacesError {
+    MissingRow(&'static str),
+    DuplicateRow(&'static str),
+    EmptyRow(&'static str),
+    Syntax(ParseError),
+    LengthMismatch { times: usize, distances: usize },
 }
 
-pub fn number_of_ways_to_beat(time: u64, distance: u64) -> u64 {
-    let mut new_records: u64 = 0;
-    for speed in 0..time {
-        let travel_time = time - speed;
-        let total_distance = speed * travel_time;
-        if total_distance > distance {
-            new_records += 1;
+impl fmt::Display for ParseRacesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingRow(label) => write!(f, "missing \"{}\" row", label),
+            Self::DuplicateRow(label) => write!(f, "more than one \"{}\" row", label),
+            Self::EmptyRow(label) => write!(f, "\"{}\" row has no numbers", label),
+            Self::Syntax(error) => write!(f, "{}", error),
+            Self::LengthMismatch { times, distances } => write!(
+                f,
+                "{} times but {} distances, expected as many of each",
+                times, distances
+            ),
         }
     }
-    new_records
 }
 
-pub fn part1(input: Vec<String>) -> u64 {
-    let times_str = input[0].strip_prefix("Time:").unwrap();
-    let times = get_digits(times_str);
-
-    let distances_str = input[1].strip_prefix("Distance:").unwrap();
-    let distances = get_digits(distances_str);
-
-    let mut all_ways_to_beat: Vec<u64> = Vec::new();
-    for i in 0..times.len() {
-        let time = times[i];
-        let distance = distances[i];
-        let ways_to_beat = number_of_ways_to_beat(time, distance);
-        if ways_to_beat > 0 {
-            all_ways_to_beat.push(ways_to_beat);
+impl Error for ParseRacesError {}
+
+#[derive(Debug, PartialEq)]
+pub struct Race {
+    pub time: BigUint,
+    pub distance: BigUint,
+}
+
+impl Race {
+    pub fn new(time: impl Into<BigUint>, distance: impl Into<BigUint>) -> Self {
+        Race {
+            time: time.into(),
+            distance: distance.into(),
+        }
+    }
+
+    /// Shortest and longest hold times that travel further than the record
+    /// distance. Races that fit in a `u64` skip the big-integer arithmetic.
+    pub fn winning_holds(&self) -> Option<(BigUint, BigUint)> {
+        match (u64::try_from(&self.time), u64::try_from(&self.distance)) {
+            (Ok(time), Ok(distance)) => {
+                winning_holds(time, distance).map(|(min, max)| (min.into(), max.into()))
+            }
+            _ => first_winning_hold_big(&self.time, &self.distance).map(|min| {
+                let max = &self.time - &min;
+                (min, max)
+            }),
+        }
+    }
+
+    /// Number of hold times that travel further than the record distance.
+    pub fn ways_to_win(&self) -> BigUint {
+        match self.winning_holds() {
+            Some((min, max)) => max + 1u32 - min,
+            None => BigUint::ZERO,
         }
     }
-    all_ways_to_beat.into_iter().product()
 }
 
-pub fn part2(input: Vec<String>) -> u64 {
-    let times_str = input[0].strip_prefix("Time:").unwrap();
-    let times: Vec<String> = get_digits(times_str)
-        .iter()
-        .map(|&n| n.to_string())
-        .collect();
+/// The `Time:` and `Distance:` rows of the input, kept as the digits that
+/// were written so they can be read either as columns or with bad kerning.
+#[derive(Debug, PartialEq)]
+pub struct Races {
+    times: Vec<String>,
+    distances: Vec<String>,
+}
 
-    let distances_str = input[1].strip_prefix("Distance:").unwrap();
-    let distances: Vec<String> = get_digits(distances_str)
-        .iter()
-        .map(|&n| n.to_string())
-        .collect();
+fn parse_number(digits: &str) -> BigUint {
+    digits.parse().unwrap()
+}
 
-    let time = times.join("").parse::<u64>().unwrap();
-    let distance = distances.join("").parse::<u64>().unwrap();
+impl Races {
+    /// One race per column, as in part 1.
+    pub fn columns(&self) -> Vec<Race> {
+        self.times
+            .iter()
+            .zip(&self.distances)
+            .map(|(time, distance)| Race::new(parse_number(time), parse_number(distance)))
+            .collect()
+    }
 
-    number_of_ways_to_beat(time, distance)
+    /// A single race read by ignoring the spaces between the numbers, as in
+    /// part 2.
+    pub fn kerned(&self) -> Race {
+        Race::new(
+            parse_number(&self.times.concat()),
+            parse_number(&self.distances.concat()),
+        )
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
+/// `Time: <digits>...` or `Distance: <digits>...`, along with its label.
+fn row<'a>() -> impl Fn(&'a str) -> PResult<'a, (&'static str, Digits<'a>)> {
+    alt(
+        map(labelled("Time", digit_groups()), |d| ("Time", d)),
+        map(labelled("Distance", digit_groups()), |d| ("Distance", d)),
+    )
+}
 
-    use super::*;
+impl FromStr for Races {
+    type Err = ParseRacesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut times: Option<Vec<String>> = None;
+        let mut distances: Option<Vec<String>> = None;
+        for (label, numbers) in parse_lines(row(), s).map_err(ParseRacesError::Syntax)? {
+            let row = if label == "Time" {
+                &mut times
+            } else {
+                &mut distances
+            };
+            if row.is_some() {
+                return Err(ParseRacesError::DuplicateRow(label));
+            }
+            // The parser has already checked every run of digits
+            let numbers: Vec<String> = numbers.flatten().map(|n| n.to_string()).collect();
+            if numbers.is_empty() {
+                return Err(ParseRacesError::EmptyRow(label));
+            }
+            *row = Some(numbers);
+        }
+
+        let times = times.ok_or(ParseRacesError::MissingRow("Time"))?;
+        let distances = distances.ok_or(ParseRacesError::MissingRow("Distance"))?;
+        if times.len() != distances.len() {
+            return Err(ParseRacesError::LengthMismatch {
+                times: times.len(),
+                distances: distances.len(),
+            });
+        }
+        Ok(Races { times, distances })
+    }
+}
+
+// Holding the button for `hold` ms travels `hold * (time - hold)` mm, which
+// beats `distance` for every hold strictly between the roots of
+// hold^2 - time * hold + distance = 0. The roots are symmetric around time / 2,
+// so finding the first winning hold is enough to count all of them.
 
-    fn inputs_path() -> &'static str {
-        "inputs"
+fn first_winning_hold(time: u64, distance: u64) -> Option<u64> {
+    let (time, distance) = (time as u128, distance as u128);
+    let discriminant = (time * time).checked_sub(4 * distance)?;
+    // The integer square root puts this at most one below the first winning hold
+    let mut hold = (time - discriminant.isqrt()) / 2;
+    while hold * (time - hold) <= distance {
+        hold += 1;
+        if hold > time / 2 {
+            return None;
+        }
+    }
+    Some(hold as u64)
+}
+
+fn first_winning_hold_big(time: &BigUint, distance: &BigUint) -> Option<BigUint> {
+    let squared = time * time;
+    let quadrupled: BigUint = distance << 2;
+    if squared < quadrupled {
+        return None;
     }
+    let mut hold: BigUint = (time - (squared - quadrupled).sqrt()) >> 1;
+    let half: BigUint = time >> 1;
+    while &hold * (time - &hold) <= *distance {
+        hold += 1u32;
+        if hold > half {
+            return None;
+        }
+    }
+    Some(hold)
+}
+
+/// Shortest and longest hold times that travel further than `distance`.
+pub fn winning_holds(time: u64, distance: u64) -> Option<(u64, u64)> {
+    first_winning_hold(time, distance).map(|min| (min, time - min))
+}
 
-    fn example_input_path() -> String {
-        format!("{}/example.txt", inputs_path())
+pub fn number_of_ways_to_beat(time: u64, distance: u64) -> u64 {
+    match winning_holds(time, distance) {
+        Some((min, max)) => max - min + 1,
+        None => 0,
     }
+}
+
+pub fn part1(input: Vec<String>) -> u64 {
+    let races: Races = input.join("\n").parse().unwrap();
 
-    fn actual_input_path() -> String {
-        format!("{}/actual.txt", inputs_path())
+    let mut all_ways_to_beat: Vec<BigUint> = Vec::new();
+    for race in races.columns() {
+        let ways_to_beat = race.ways_to_win();
+        if ways_to_beat > BigUint::ZERO {
+            all_ways_to_beat.push(ways_to_beat);
+        }
     }
+    let product: BigUint = all_ways_to_beat.into_iter().product();
+    u64::try_from(product).unwrap()
+}
+
+pub fn part2(input: Vec<String>) -> u64 {
+    let races: Races = input.join("\n").parse().unwrap();
+    u64::try_from(races.kerned().ways_to_win()).unwrap()
+}
+
+const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE,
+        answer: 288,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE,
+        answer: 71503,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    pub fn part1_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().map(|l| l.to_string()).collect();
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 288);
+    pub fn parse_races_in_either_order() {
+        let races: Races = "Distance:  9  40  200\nTime:      7  15   30"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            races.columns(),
+            vec![
+                Race::new(7u32, 9u32),
+                Race::new(15u32, 40u32),
+                Race::new(30u32, 200u32)
+            ]
+        );
+        assert_eq!(races.kerned(), Race::new(71530u32, 940200u32));
     }
 
     #[test]
-    pub fn part1_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().map(|l| l.to_string()).collect();
-        let result = part1(input);
-        println!("{}", result);
-        assert_eq!(result, 140220);
+    pub fn parse_races_rejects_bad_rows() {
+        assert_eq!(
+            "Time: 7 15\nDistance: 9".parse::<Races>(),
+            Err(ParseRacesError::LengthMismatch {
+                times: 2,
+                distances: 1
+            })
+        );
+        assert_eq!(
+            "Time: 7".parse::<Races>(),
+            Err(ParseRacesError::MissingRow("Distance"))
+        );
+        assert_eq!(
+            "Time: 7\nTime: 8\nDistance: 9".parse::<Races>(),
+            Err(ParseRacesError::DuplicateRow("Time"))
+        );
+        assert_eq!(
+            "Time:\nDistance:".parse::<Races>(),
+            Err(ParseRacesError::EmptyRow("Time"))
+        );
+        assert_eq!(
+            "Time: 7\nDistance: -9"
+                .parse::<Races>()
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 11: expected number, found \"-9\""
+        );
+        assert_eq!(
+            "Time: 7\nSpeed: 9"
+                .parse::<Races>()
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 1: expected \"Time\" or \"Distance\", found \"Speed:\""
+        );
     }
 
     #[test]
-    pub fn part2_example_input() {
-        let file_content = fs::read_to_string(example_input_path()).unwrap();
-        let input = file_content.lines().map(|l| l.to_string()).collect();
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 71503);
+    pub fn ways_to_win_matches_brute_force() {
+        for time in 0..60u64 {
+            for distance in 0..=time * time / 4 + 1 {
+                let expected = (0..=time).filter(|h| h * (time - h) > distance).count();
+                assert_eq!(
+                    number_of_ways_to_beat(time, distance),
+                    expected as u64,
+                    "time {} distance {}",
+                    time,
+                    distance
+                );
+                assert_eq!(
+                    first_winning_hold_big(&time.into(), &distance.into()).map(|h| h.to_string()),
+                    first_winning_hold(time, distance).map(|h| h.to_string())
+                );
+            }
+        }
     }
 
     #[test]
-    pub fn part2_actual_input() {
-        let file_content = fs::read_to_string(actual_input_path()).unwrap();
-        let input = file_content.lines().map(|l| l.to_string()).collect();
-        let result = part2(input);
-        println!("{}", result);
-        assert_eq!(result, 39570185);
+    pub fn ways_to_win_beyond_u64() {
+        let races: Races = "Time: 1844674407 3709551616\nDistance: 0 0"
+            .parse()
+            .unwrap();
+        assert_eq!(races.kerned().ways_to_win(), BigUint::from(u64::MAX));
+
+        // Only holding for exactly half the time beats k^2 - 1
+        let half: BigUint = "10000000000000000000000000000000000000000".parse().unwrap();
+        let square = &half * &half;
+        let race = Race::new(&half << 1, &square - 1u32);
+        assert_eq!(race.ways_to_win(), BigUint::from(1u32));
+        let race = Race::new(&half << 1, square);
+        assert_eq!(race.ways_to_win(), BigUint::ZERO);
+    }
+
+    aoc_core::example_tests! {
+        EXAMPLES;
+        part1_examples: part1(1);
+        part2_examples: part2(2);
+    }
+
+    aoc_core::answer_tests! {
+        day 6;
+        part1_actual_input: part1(actual) == 140220;
+        part2_actual_input: part2(actual) == 39570185;
     }
 }
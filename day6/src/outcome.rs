@@ -0,0 +1,116 @@
+use aoc_core::table::{self, Align};
+use num_bigint::BigUint;
+
+use super::Race;
+
+/// Everything worth knowing about how a race can be won.
+#[derive(Debug, PartialEq)]
+pub struct Outcome {
+    /// Shortest and longest hold times that beat the record, if any do.
+    pub winning_holds: Option<(BigUint, BigUint)>,
+    pub ways_to_win: BigUint,
+    /// Hold time travelling the furthest. When the race time is odd, holding
+    /// one millisecond longer goes just as far.
+    pub optimal_hold: BigUint,
+    pub max_distance: BigUint,
+}
+
+impl Race {
+    pub fn outcome(&self) -> Outcome {
+        let optimal_hold: BigUint = &self.time >> 1;
+        let max_distance = &optimal_hold * (&self.time - &optimal_hold);
+        Outcome {
+            winning_holds: self.winning_holds(),
+            ways_to_win: self.ways_to_win(),
+            optimal_hold,
+            max_distance,
+        }
+    }
+}
+
+pub fn render_table(races: &[Race]) -> String {
+    let rows: Vec<[String; 6]> = races
+        .iter()
+        .map(|race| {
+            let outcome = race.outcome();
+            [
+                race.time.to_string(),
+                race.distance.to_string(),
+                match &outcome.winning_holds {
+                    Some((min, max)) => format!("[{}, {}]", min, max),
+                    None => "-".to_string(),
+                },
+                outcome.ways_to_win.to_string(),
+                outcome.optimal_hold.to_string(),
+                outcome.max_distance.to_string(),
+            ]
+        })
+        .collect();
+
+    table::render(
+        [
+            ("Time", Align::Right),
+            ("Record", Align::Right),
+            ("Winning holds", Align::Left),
+            ("Ways", Align::Right),
+            ("Best hold", Align::Right),
+            ("Max distance", Align::Right),
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Races;
+    use super::*;
+
+    fn example_races() -> Races {
+        crate::EXAMPLE.parse().unwrap()
+    }
+
+    #[test]
+    pub fn outcome_example() {
+        let races = example_races().columns();
+        let outcomes: Vec<Outcome> = races.iter().map(|r| r.outcome()).collect();
+        assert_eq!(
+            outcomes[0],
+            Outcome {
+                winning_holds: Some((2u32.into(), 5u32.into())),
+                ways_to_win: 4u32.into(),
+                optimal_hold: 3u32.into(),
+                max_distance: 12u32.into(),
+            }
+        );
+        assert_eq!(
+            outcomes[2].winning_holds,
+            Some((11u32.into(), 19u32.into()))
+        );
+        assert_eq!(
+            example_races().kerned().outcome().winning_holds,
+            Some((14u32.into(), 71516u32.into()))
+        );
+    }
+
+    #[test]
+    pub fn outcome_without_winning_holds() {
+        let outcome = Race::new(4u32, 4u32).outcome();
+        assert_eq!(outcome.winning_holds, None);
+        assert_eq!(outcome.ways_to_win, BigUint::ZERO);
+        assert_eq!(outcome.max_distance, BigUint::from(4u32));
+    }
+
+    #[test]
+    pub fn render_example() {
+        let table = render_table(&example_races().columns());
+        println!("{}", table);
+        assert_eq!(
+            table.lines().next().unwrap(),
+            "Time | Record | Winning holds | Ways | Best hold | Max distance"
+        );
+        assert_eq!(
+            table.lines().nth(3).unwrap(),
+            "  30 |    200 | [11, 19]      |    9 |        15 |          225"
+        );
+    }
+}